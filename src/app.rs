@@ -1,35 +1,63 @@
 use crate::{
     modules::{
+        command::{Command, path_matches},
+        config::Config,
+        display::{
+            DEFAULT_HEIGHT, DisplayItem, DisplayMode, DisplayedSignal, analog_levels,
+            parse_value_map,
+        },
+        error::WaveError,
+        export::{MAX_CSV_ROWS, view_to_svg, write_csv},
+        history::History,
+        keymap::{Action, KeyMap},
         module::Module,
-        signal::{DisplayEvent, Signal, ValueDisplayEvent, VectorDisplayEvent},
+        session::{Session, SessionItem},
+        signal::{
+            DisplayEvent, Signal, ValueDisplayEvent, ValuePattern, ValueType, VectorDisplayEvent,
+            vector_to_base_10,
+        },
+        theme::Theme,
         time::Time,
     },
-    ui::{
-        M_CHANGE, M_MULTIPLE, M_STAY, S_FALLING_EDGE, S_MULTIPLE, S_RISING_EDGE, S_STAY_0,
-        S_STAY_1, S_STAY_X, S_STAY_Z,
+    ui::{Glyphs, KEY_BINDINGS, MOUSE_HELP, UNICODE_GLYPHS},
+    utils::{
+        RULER_TICK_SPACING, STDIN_PATH, bookmark_line, density_index, elide_middle, middle_str,
+        popup_area, ruler_with_spacing,
     },
-    utils::{middle_str, parse_files, vector_contain_x_or_z},
+    waveform::{LoadOptions, Waveform, load_with_progress},
 };
 
 use std::{
     cell::RefCell,
     cmp::{max, min},
-    io::{self},
-    rc::Rc,
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, BufWriter, Write},
+    path::Path,
+    rc::{Rc, Weak},
 };
 
 use cli_log::debug;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::{
     DefaultTerminal,
-    layout::{Constraint, Direction, Flex, Layout, Rect},
-    style::{Color, Style},
+    layout::{Constraint, Direction, Flex, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{self, Block, Borders, Paragraph},
+    widgets::{self, Block, Borders, Gauge, Paragraph, Wrap},
 };
+use regex::Regex;
 use std::str::FromStr;
 use tui_textarea::TextArea;
-use vcd::{Value, Vector};
+use vcd::{ScopeType, TimescaleUnit, Value, Vector};
+
+/// Waveform file opened when no file is given on the command line
+pub const DEFAULT_FILE_PATH: &str = "./assets/verilog/test_1.vcd";
+
+/// Number of changes that can be undone
+const UNDO_LIMIT: usize = 100;
 
 #[derive(PartialEq)]
 enum AppMode {
@@ -37,13 +65,145 @@ enum AppMode {
     Input,
     Exit,
     AddSignal,
+    Help,
+    RestoreSession,
+    AddDivider,
+    ExportCsv,
+    Rename,
+    Command,
+    AddBookmark,
+    Bookmarks,
+    Info,
+    Unknowns,
+    ConfirmClear,
+}
+
+/// What the time entered in the input mode is applied to
+#[derive(Clone, Copy, PartialEq)]
+enum InputTarget {
+    // The left edge of the view
+    Start,
+    // The middle of the view
+    Center,
+    // The time of a column
+    Step,
+}
+
+impl InputTarget {
+    fn prompt(self) -> &'static str {
+        match self {
+            InputTarget::Start | InputTarget::Center => "Enter a time (e.g. 100ns)",
+            InputTarget::Step => "Enter the time of a column (e.g. 25ns)",
+        }
+    }
+
+    /// Parse the entered time, a step must be positive
+    fn parse(self, input: &str) -> Result<Time, String> {
+        let time = Time::from_str(input).map_err(|e| e.message().to_string())?;
+        if self == InputTarget::Step && time.time() == 0 {
+            return Err("Step must be positive".to_string());
+        }
+        Ok(time)
+    }
+}
+
+/// Snapshot of the view and the displayed rows for the undo history
+/// Signals are weak references, so that snapshots do not keep the events of a file alive
+#[derive(PartialEq)]
+struct ViewState {
+    items: Vec<ItemState>,
+    time_start: Time,
+    time_step: Time,
+}
+
+/// Snapshot of a displayed row, see `DisplayItem`
+enum ItemState {
+    Signal(
+        Weak<RefCell<Signal>>,
+        DisplayMode,
+        Option<String>,
+        Option<HashMap<u64, String>>,
+        u16,
+    ),
+    Divider(String),
+}
+
+impl PartialEq for ItemState {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                ItemState::Signal(a, a_mode, a_alias, a_map, a_height),
+                ItemState::Signal(b, b_mode, b_alias, b_map, b_height),
+            ) => {
+                a.ptr_eq(b)
+                    && a_mode == b_mode
+                    && a_alias == b_alias
+                    && a_map == b_map
+                    && a_height == b_height
+            }
+            (ItemState::Divider(a), ItemState::Divider(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Display events of the signals over the visible window, kept between frames
+/// The events are computed again when the view or the number of events of a signal changes
+#[derive(Default)]
+struct EventCache {
+    // Start time, time step and number of columns of the view the events are computed for
+    view: (u64, u64, usize),
+    // Events of each signal by its address, with the number of events of the signal
+    events: HashMap<usize, (usize, Rc<Vec<DisplayEvent>>)>,
+}
+
+/// First differences of the compared signals by the addresses of both signals, with their
+/// numbers of events, see `App::first_difference`
+type DiffCache = HashMap<(usize, usize), (usize, usize, Option<u64>)>;
+
+/// Paths of the signals by their addresses, and the signals of the same path in each file, kept
+/// next to `App::signals` as they are looked up for every displayed row on every frame
+#[derive(Default)]
+struct SignalIndex {
+    // Path as listed in `App::signals` and path without the tag of the file, by the address of
+    // the signal
+    paths: HashMap<*const RefCell<Signal>, (String, String)>,
+    // Signals by their paths without the tag of their file, in the order of the files
+    untagged: HashMap<String, Vec<Rc<RefCell<Signal>>>>,
+}
+
+impl SignalIndex {
+    fn insert(&mut self, path: String, untagged: String, signal: &Rc<RefCell<Signal>>) {
+        self.untagged
+            .entry(untagged.clone())
+            .or_default()
+            .push(Rc::clone(signal));
+        self.paths.insert(Rc::as_ptr(signal), (path, untagged));
+    }
+}
+
+/// A row of the add-signal picker
+enum PickerRow {
+    // Key of the module in `App::collapsed`, its name and indent level
+    Module(String, String, usize),
+    // The signal, its indent level and the key of the module it is listed under
+    Signal(Rc<RefCell<Signal>>, usize, Option<String>),
 }
 
 pub struct App<'a> {
-    module_root: Rc<RefCell<Module>>,
-    signals: Vec<Rc<RefCell<Signal>>>,
-    displayed_signals: Vec<Rc<RefCell<Signal>>>,
-    undisplayed_signals: Vec<Rc<RefCell<Signal>>>,
+    // Paths of the loaded waveform files, the session is saved next to the first one
+    file_paths: Vec<String>,
+    // Options the files are loaded with, again when they are reloaded
+    options: LoadOptions,
+    // Each loaded file, paired with its tag
+    waveforms: Vec<(String, Waveform)>,
+    // All signals paired with their paths
+    // When several files are loaded, the paths are prefixed by the tag of the file, e.g.
+    // "golden:test_tb->i1:clk(\")"
+    signals: Vec<(String, Rc<RefCell<Signal>>)>,
+    // Built with `signals`, see `App::signal_path` and `App::counterpart`
+    signal_index: SignalIndex,
+    displayed_items: Vec<DisplayItem>,
     time_start: Time,
     time_step: Time,
     arr_size: usize,
@@ -51,58 +211,795 @@ pub struct App<'a> {
     mode: AppMode,
     choice_index: usize,
     textarea: TextArea<'a>,
-}
-
-fn filter_displayed_signals(
-    all_signals: &Vec<Rc<RefCell<Signal>>>,
-    displayed_signals: &Vec<Rc<RefCell<Signal>>>,
-) -> Vec<Rc<RefCell<Signal>>> {
-    all_signals
-        .iter()
-        .filter(|e2| !displayed_signals.iter().any(|e1| Rc::ptr_eq(e1, e2)))
-        .cloned()
-        .collect()
+    // What the time entered in the input mode is applied to
+    input_target: InputTarget,
+    // Filter of the signals in the add-signal picker
+    filter: TextArea<'a>,
+    filter_editing: bool,
+    // Keys of the modules collapsed in the add-signal picker, i.e. their paths (see
+    // `Module::get_path_str`), prefixed by the tag of the file when several files are loaded
+    collapsed: HashSet<String>,
+    // Column of the measurement cursor in the waveform area
+    cursor: usize,
+    // Column where dragging on a waveform started, the selected range spans to the cursor
+    selection: Option<usize>,
+    // Times of the two measurement markers
+    marker_a: Option<u64>,
+    marker_b: Option<u64>,
+    // Time in fs the labels of the ruler are relative to, the other times stay absolute
+    time_origin: u64,
+    // Index of the focused row in `displayed_items`
+    focus: usize,
+    // Time of the last event in the file
+    max_time: u64,
+    // One-off message shown in the status line, cleared on the next key press
+    status_message: Option<String>,
+    // Areas of the name and the waveform of each displayed row in the last drawn frame
+    // Dividers have an empty waveform area
+    signal_areas: Vec<(Rect, Rect)>,
+    // Whether grid lines are drawn down from the ticks of the time ruler
+    show_grid: bool,
+    // Whether each signal is drawn on a single row, see `App::compact_line`
+    compact: bool,
+    // Whether the columns where a signal differs from its counterpart in another file are
+    // highlighted, see `App::counterpart`
+    diff: bool,
+    keymap: KeyMap,
+    // Minimum number of columns between two ticks of the time ruler
+    tick_spacing: u64,
+    // Characters the waveforms are drawn with, `ASCII_GLYPHS` for terminals without Unicode
+    glyphs: &'static Glyphs,
+    history: History<ViewState>,
+    // Times in fs and labels of the bookmarks, sorted by time
+    bookmarks: Vec<(u64, String)>,
+    // Value searched in the focused signal, whose matches `n` and `N` go through
+    search: Option<ValuePattern>,
+    // Times and signals of the `x` and `z` values listed by `:xz`, sorted by time
+    unknowns: Vec<(u64, Rc<RefCell<Signal>>)>,
+    event_cache: RefCell<EventCache>,
+    // Cleared when the comparison is toggled and on reload
+    first_differences: RefCell<DiffCache>,
+    // Clock periods of the signals by their addresses, with their numbers of events, see
+    // `App::clock_period`
+    clock_periods: RefCell<HashMap<usize, (usize, Option<u64>)>>,
+    // Watcher of the files reloading them when they change, see `App::watch_files`
+    #[cfg(feature = "watch")]
+    watcher: Option<crate::utils::watch::FileWatcher>,
+    theme: Theme,
 }
 
 impl<'a> App<'a> {
-    pub fn default() -> io::Result<Self> {
-        let (module_root, time_base_scale) =
-            parse_files(String::from("./assets/verilog/test_1.vcd"))?;
-        debug!("Root: {}", module_root.borrow());
-        let signals = module_root.borrow().get_signals();
-        let undisplayed_signals = filter_displayed_signals(&signals, &vec![]);
+    pub fn default() -> Result<Self, WaveError> {
+        Self::new(vec![String::from(DEFAULT_FILE_PATH)])
+    }
+
+    /// Load one or more waveform files, the time base of the view is taken from the first one
+    pub fn new(file_paths: Vec<String>) -> Result<Self, WaveError> {
+        Self::with_progress(file_paths, &LoadOptions::default(), &mut |_, _, _| {})
+    }
+
+    /// Same as `new`, calling `progress` with the file being parsed, the bytes read so far and
+    /// the size of the file
+    pub fn with_progress(
+        file_paths: Vec<String>,
+        options: &LoadOptions,
+        progress: &mut dyn FnMut(&str, u64, u64),
+    ) -> Result<Self, WaveError> {
+        let tags = file_tags(&file_paths);
+        let mut waveforms = vec![];
+        let mut warnings = vec![];
+        let mut time_base_scale = TimescaleUnit::NS;
+        for (index, (file_path, tag)) in file_paths.iter().zip(tags).enumerate() {
+            let waveform = load_with_progress(file_path, options, &mut |read, total| {
+                progress(file_path, read, total)
+            })?;
+            debug!("Root of {}: {}", file_path, waveform.root().borrow());
+            if index == 0 {
+                time_base_scale = waveform.timescale();
+            }
+            warnings.extend(
+                waveform
+                    .warnings()
+                    .iter()
+                    .map(|x| format!("{}: {}", file_path, x)),
+            );
+            waveforms.push((tag, waveform));
+        }
+
+        let mut signals = vec![];
+        let mut signal_index = SignalIndex::default();
+        for (tag, waveform) in &waveforms {
+            for (path, signal) in waveform.root().borrow().all_signals_with_paths() {
+                let tagged = match waveforms.len() {
+                    1 => path.clone(),
+                    _ => format!("{}:{}", tag, path),
+                };
+                signal_index.insert(tagged.clone(), path, &signal);
+                signals.push((tagged, signal));
+            }
+        }
+        let max_time = waveforms
+            .iter()
+            .map(|(_, waveform)| waveform.max_time())
+            .max()
+            .unwrap_or(0);
+
+        // Offer to restore the session saved next to the waveform file
+        let mode = if Session::path_for(&file_paths[0]).exists() {
+            AppMode::RestoreSession
+        } else {
+            AppMode::AddSignal
+        };
 
         Ok(Self {
-            mode: AppMode::AddSignal,
-            module_root,
+            mode,
+            file_paths,
+            options: options.clone(),
+            waveforms,
             signals,
-            displayed_signals: vec![],
-            undisplayed_signals,
+            signal_index,
+            displayed_items: vec![],
             time_start: Time::new(0, time_base_scale),
             time_step: Time::new(10, time_base_scale),
             arr_size: 100,
             choice_index: 0,
             textarea: TextArea::default(),
+            input_target: InputTarget::Start,
+            filter: TextArea::default(),
+            filter_editing: false,
+            collapsed: HashSet::new(),
+            cursor: 0,
+            selection: None,
+            marker_a: None,
+            marker_b: None,
+            time_origin: 0,
+            focus: 0,
+            max_time,
+            status_message: match warnings.is_empty() {
+                true => None,
+                false => Some(warnings.join(" | ")),
+            },
+            signal_areas: vec![],
+            show_grid: true,
+            compact: false,
+            diff: false,
+            keymap: KeyMap::default(),
+            tick_spacing: RULER_TICK_SPACING,
+            glyphs: &UNICODE_GLYPHS,
+            history: History::new(UNDO_LIMIT),
+            bookmarks: vec![],
+            search: None,
+            unknowns: vec![],
+            event_cache: RefCell::default(),
+            first_differences: RefCell::default(),
+            clock_periods: RefCell::default(),
+            #[cfg(feature = "watch")]
+            watcher: None,
+            theme: Theme::default(),
         })
     }
 
+    /// Use `keymap` for the keys of the normal mode, see `KeyMap::load`
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
+    /// Use the settings of the config file, see `Config::load`
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::{app::App, command::Command, config::Config, time::Time};
+    /// use ratatui::{Terminal, backend::TestBackend};
+    /// use std::str::FromStr;
+    ///
+    /// let mut app = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).unwrap();
+    /// app.run_command(Command::Add("test_tb:clk".to_string()));
+    /// app.run_command(Command::Step(Time::from_str("1ns").unwrap()));
+    /// app.set_config(Config::from_toml("[display]\nascii = true").unwrap());
+    ///
+    /// let mut terminal = Terminal::new(TestBackend::new(100, 16)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// let screen: String = buffer.content().iter().map(|x| x.symbol()).collect();
+    /// // clk rises at 10ns and falls at 20ns
+    /// assert!(screen.contains("/---------\\"));
+    /// assert!(screen.contains("\\_________/"));
+    /// // The borders too are drawn in ASCII
+    /// assert!(screen.contains("+--------+"));
+    /// assert!(screen.is_ascii());
+    /// ```
+    pub fn set_config(&mut self, config: Config) {
+        self.glyphs = config.glyphs();
+        self.keymap = config.keymap;
+        self.tick_spacing = config.tick_spacing;
+    }
+
+    /// A box drawn with the borders of the glyphs in use
+    fn block<'b>(&self) -> Block<'b> {
+        Block::default().border_set(self.glyphs.border)
+    }
+
+    /// Save the displayed signals and the view to a session file
+    pub fn save_session(&self, path: &Path) -> io::Result<()> {
+        Session {
+            items: self
+                .displayed_items
+                .iter()
+                .filter_map(|item| match item {
+                    DisplayItem::Signal(x) => {
+                        Some(SessionItem::Signal(self.signal_path(&x.signal)?.clone()))
+                    }
+                    DisplayItem::Divider(label) => Some(SessionItem::Divider {
+                        divider: label.clone(),
+                    }),
+                })
+                .collect(),
+            aliases: self
+                .displayed_signals()
+                .filter_map(|x| Some((self.signal_path(&x.signal)?.clone(), x.alias.clone()?)))
+                .collect(),
+            modes: self
+                .displayed_signals()
+                .filter_map(|x| Some((self.signal_path(&x.signal)?.clone(), x.mode)))
+                .collect(),
+            value_maps: self
+                .displayed_signals()
+                .filter_map(|x| {
+                    let mut map: Vec<(u64, String)> = x.value_map.clone()?.into_iter().collect();
+                    map.sort();
+                    Some((self.signal_path(&x.signal)?.clone(), map))
+                })
+                .collect(),
+            time_start: self.time_start.time(),
+            time_step: self.time_step.time(),
+            bookmarks: self.bookmarks.clone(),
+        }
+        .save(path)
+    }
+
+    /// Export the displayed signals over the visible window to an SVG file
+    pub fn export_svg(&self, path: &Path) -> io::Result<()> {
+        let rows: Vec<(String, Vec<DisplayEvent>)> = self
+            .displayed_signals()
+            .map(|x| {
+                let signal = x.signal.borrow();
+                (
+                    x.output_name(),
+                    signal.events_arr_in_range(
+                        self.time_start.time(),
+                        self.time_step.time(),
+                        self.arr_size,
+                    ),
+                )
+            })
+            .collect();
+        fs::write(
+            path,
+            view_to_svg(&rows, self.time_start.time(), self.time_step.time()),
+        )
+    }
+
+    /// Export the displayed signals to a CSV file, sampled at each column of the visible window,
+    /// or at each step between the markers A and B when both are set
+    /// More rows than `MAX_CSV_ROWS` are refused, a larger step makes fewer rows
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::{app::App, command::Command, time::Time};
+    /// use std::str::FromStr;
+    ///
+    /// let mut app = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// let press = |app: &mut App, code| app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    /// press(&mut app, KeyCode::Esc).unwrap();
+    /// app.run_command(Command::Add("test_tb:clk".to_string()));
+    /// app.run_command(Command::Step(Time::from_str("5ns").unwrap()));
+    /// for _ in 0..2 {
+    ///     press(&mut app, KeyCode::Right).unwrap();
+    /// }
+    /// press(&mut app, KeyCode::Char('1')).unwrap();
+    /// press(&mut app, KeyCode::Right).unwrap();
+    /// press(&mut app, KeyCode::Char('2')).unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("rata_wave_doctest.csv");
+    /// app.export_csv(&path).unwrap();
+    /// let csv = std::fs::read_to_string(&path).unwrap();
+    /// // The header, then the rows at 10ns and 15ns
+    /// assert_eq!(csv.lines().count(), 3);
+    /// assert!(csv.ends_with("\n10000000,1\n15000000,1\n"));
+    /// std::fs::remove_file(&path).unwrap();
+    ///
+    /// // Far too many rows between the markers at a step of 1fs
+    /// app.run_command(Command::Step(Time::from_str("1fs").unwrap()));
+    /// let error = app.export_csv(&path).unwrap_err();
+    /// assert_eq!(error.to_string(), "5000001 rows, more than 1000000, use a larger step");
+    /// assert!(!path.exists());
+    /// ```
+    pub fn export_csv(&self, path: &Path) -> io::Result<()> {
+        let signals: Vec<(String, &DisplayedSignal)> = self
+            .displayed_signals()
+            .map(|x| {
+                let path = self.signal_path(&x.signal).cloned().unwrap_or_default();
+                (path, x)
+            })
+            .collect();
+        let step = self.time_step.time();
+        let (start, rows) = match (self.marker_a, self.marker_b) {
+            (Some(a), Some(b)) => (min(a, b), a.abs_diff(b) / step + 1),
+            _ => (self.time_start.time(), self.arr_size as u64),
+        };
+        if rows > MAX_CSV_ROWS as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} rows, more than {}, use a larger step",
+                    rows, MAX_CSV_ROWS
+                ),
+            ));
+        }
+        let mut out = BufWriter::new(fs::File::create(path)?);
+        write_csv(&mut out, &signals, start, step, rows as usize)?;
+        out.flush()
+    }
+
+    /// Parse the waveform files again, e.g. after the simulation is run again
+    /// The displayed signals are found again by their paths, keeping their formats, and the view
+    /// is kept, signals that no longer exist are dropped
+    /// Return the number of dropped signals
+    /// The standard input cannot be read again, so files read from it are not reloaded
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::{app::App, command::Command};
+    /// use ratatui::{Terminal, backend::TestBackend};
+    ///
+    /// let path = std::env::temp_dir().join("rata_wave_reload.vcd");
+    /// let content = std::fs::read_to_string("assets/verilog/test_1.vcd").unwrap();
+    /// std::fs::write(&path, &content).unwrap();
+    /// let mut app = App::new(vec![path.display().to_string()]).unwrap();
+    /// app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).unwrap();
+    /// for command in ["add test_tb:rst", "add test_tb:counter", "radix hex", "step 2ns"] {
+    ///     app.run_command(command.parse::<Command>().unwrap());
+    /// }
+    /// app.run_command("goto 300ns".parse::<Command>().unwrap());
+    /// app.run_command("xz all".parse::<Command>().unwrap());
+    /// let mut terminal = Terminal::new(TestBackend::new(140, 20)).unwrap();
+    /// let mut screen = |app: &mut App| {
+    ///     terminal.draw(|frame| app.draw(frame)).unwrap();
+    ///     let buffer = terminal.backend().buffer();
+    ///     buffer.content().iter().map(|x| x.symbol()).collect::<String>()
+    /// };
+    /// let before = screen(&mut app);
+    ///
+    /// // The simulation is run again without `test_tb.rst`
+    /// std::fs::write(&path, content.replacen("$var reg 1 # rst $end\n", "", 1)).unwrap();
+    /// assert_eq!(app.reload().unwrap(), 1);
+    /// let after = screen(&mut app);
+    /// assert!(before.contains("test_tb:rst") && !after.contains("test_tb:rst"));
+    /// // The counter keeps its mode, and the view its step and start
+    /// assert!(after.contains("[hex]"));
+    /// assert!(before.contains("Step: 2ns | Window: [200ns, 444ns]"));
+    /// assert!(after.contains("Step: 2ns | Window: [200ns, 444ns]"));
+    /// // The list of the `x` and `z` values of the old signals is closed
+    /// assert!(before.contains("x and z values") && !after.contains("x and z values"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn reload(&mut self) -> Result<usize, WaveError> {
+        if self.file_paths.iter().any(|x| x == STDIN_PATH) {
+            return Err(WaveError::UnsupportedFeature(
+                "reloading the standard input".to_string(),
+            ));
+        }
+        let reloaded =
+            App::with_progress(self.file_paths.clone(), &self.options, &mut |_, _, _| {})?;
+
+        let mut dropped = 0;
+        let reloaded_signals: HashMap<&String, &Rc<RefCell<Signal>>> = reloaded
+            .signals
+            .iter()
+            .map(|(x, signal)| (x, signal))
+            .collect();
+        let items = std::mem::take(&mut self.displayed_items);
+        self.displayed_items = items
+            .into_iter()
+            .filter_map(|item| match item {
+                DisplayItem::Signal(mut displayed) => {
+                    let path = self.signal_path(&displayed.signal);
+                    match path.and_then(|x| reloaded_signals.get(x)) {
+                        Some(signal) => {
+                            displayed.signal = Rc::clone(signal);
+                            Some(DisplayItem::Signal(displayed))
+                        }
+                        None => {
+                            dropped += 1;
+                            None
+                        }
+                    }
+                }
+                divider => Some(divider),
+            })
+            .collect();
+
+        self.waveforms = reloaded.waveforms;
+        self.signals = reloaded.signals;
+        self.signal_index = reloaded.signal_index;
+        self.max_time = reloaded.max_time;
+        self.focus = min(self.focus, self.displayed_items.len().saturating_sub(1));
+        self.history = History::new(UNDO_LIMIT);
+        self.event_cache = RefCell::default();
+        self.first_differences = RefCell::default();
+        self.clock_periods = RefCell::default();
+        // The `x` and `z` values listed by `:xz` are of the old signals
+        self.unknowns.clear();
+        if self.mode == AppMode::Unknowns {
+            self.mode = AppMode::Run;
+        }
+        self.clamp_time_start();
+        Ok(dropped)
+    }
+
+    /// Restore the displayed signals and the view from a session file
+    /// Signals that no longer exist in the waveform are skipped
+    /// Return the number of skipped signals
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::{app::App, command::Command, display::DisplayMode};
+    /// use ratatui::{Terminal, backend::TestBackend};
+    ///
+    /// let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    /// let mut app = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// app.handle_key_event(key(KeyCode::Esc)).unwrap();
+    /// app.run_command(Command::Add("test_tb:clk".to_string()));
+    /// // A divider below the clock
+    /// app.handle_key_event(key(KeyCode::Char('d'))).unwrap();
+    /// "clocks".chars().for_each(|c| app.handle_key_event(key(KeyCode::Char(c))).unwrap());
+    /// app.handle_key_event(key(KeyCode::Enter)).unwrap();
+    /// // A vector shown in hex
+    /// app.run_command(Command::Add("test_tb:counter".to_string()));
+    /// app.run_command(Command::Radix(DisplayMode::Hex));
+    /// let path = std::env::temp_dir().join("rata_wave_divider.ratawave");
+    /// app.save_session(&path).unwrap();
+    ///
+    /// let mut restored = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// restored.handle_key_event(key(KeyCode::Esc)).unwrap();
+    /// assert_eq!(restored.load_session(&path).unwrap(), 0);
+    /// let mut terminal = Terminal::new(TestBackend::new(100, 16)).unwrap();
+    /// terminal.draw(|frame| restored.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// let rows: Vec<String> = (0..16)
+    ///     .map(|y| (0..100).map(|x| buffer[(x, y)].symbol()).collect())
+    ///     .collect();
+    /// let row_of = |text| rows.iter().position(|row| row.contains(text)).unwrap();
+    /// // The divider is restored below the clock, whose elided name ends with its type
+    /// assert!(row_of("(\")") < row_of("clocks"));
+    /// assert!(row_of("clocks") < row_of("[hex]"));
+    /// ```
+    pub fn load_session(&mut self, path: &Path) -> io::Result<usize> {
+        let session = Session::load(path)?;
+
+        let mut skipped = 0;
+        let signals: HashMap<&String, &Rc<RefCell<Signal>>> =
+            self.signals.iter().map(|(x, signal)| (x, signal)).collect();
+        let displayed_items = session
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                SessionItem::Signal(path) => {
+                    let found = signals.get(path);
+                    skipped += found.is_none() as usize;
+                    found.map(|signal| {
+                        let mut displayed = DisplayedSignal::new(Rc::clone(signal));
+                        displayed.alias = session.aliases.get(path).cloned();
+                        if let Some(mode) = session.modes.get(path) {
+                            displayed.mode = *mode;
+                        }
+                        displayed.value_map = session
+                            .value_maps
+                            .get(path)
+                            .map(|map| map.iter().cloned().collect());
+                        DisplayItem::Signal(displayed)
+                    })
+                }
+                SessionItem::Divider { divider } => Some(DisplayItem::Divider(divider.clone())),
+            })
+            .collect();
+        self.displayed_items = displayed_items;
+        self.choice_index = 0;
+        self.focus = 0;
+        self.bookmarks = session.bookmarks;
+        self.bookmarks.sort_by_key(|(time, _)| *time);
+
+        self.time_start = Time::new(session.time_start, TimescaleUnit::FS);
+        self.time_step = Time::new(max(1, session.time_step), TimescaleUnit::FS);
+        self.clamp_time_start();
+
+        Ok(skipped)
+    }
+
+    /// Reload the files whenever they change, e.g. while a simulation writes them
+    #[cfg(feature = "watch")]
+    pub fn watch_files(&mut self) {
+        self.watcher = Some(crate::utils::watch::FileWatcher::new(&self.file_paths));
+    }
+
+    /// Reload the files if the watcher saw them change
+    #[cfg(feature = "watch")]
+    fn reload_if_changed(&mut self) {
+        if self.watcher.as_mut().is_some_and(|x| x.poll()) {
+            self.status_message = Some(match self.reload() {
+                Ok(0) => "Reloaded the changed files".to_string(),
+                Ok(dropped) => format!(
+                    "Reloaded the changed files, {} signals no longer exist",
+                    dropped
+                ),
+                Err(e) => format!("Failed to reload: {}", e),
+            });
+        }
+    }
+
     fn handle_events(&mut self) -> io::Result<()> {
+        // Wake up regularly to check the watched files
+        #[cfg(feature = "watch")]
+        if self.watcher.is_some() {
+            let pressed = event::poll(crate::utils::watch::POLL_INTERVAL)?;
+            self.reload_if_changed();
+            if !pressed {
+                return Ok(());
+            }
+        }
         match event::read()? {
             // it's important to check that the event is a key press event as
             // crossterm also emits key release and repeat events on Windows.
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event)?
             }
+            Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
             _ => {}
         };
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut ratatui::Frame<'_>) {
+    /// Handle a mouse event in the normal mode, using the areas of the last drawn frame
+    /// Dragging across a waveform selects a time range, zooming into it on release, which can be
+    /// undone
+    /// The wheel zooms around the pointer, and scrolls the view with Shift held
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent};
+    /// use crossterm::event::MouseEventKind::{Down, Drag, ScrollDown, ScrollUp, Up};
+    /// use rata_wave::{app::App, command::Command, time::Time};
+    /// use ratatui::{Terminal, backend::TestBackend};
+    /// use std::str::FromStr;
+    ///
+    /// let mut app = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).unwrap();
+    /// app.run_command(Command::Add("test_tb:clk".to_string()));
+    /// app.run_command(Command::Step(Time::from_str("1ns").unwrap()));
+    /// let mut terminal = Terminal::new(TestBackend::new(100, 16)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// // clk rises at 10ns, in the column of its first rising edge with a step of 1ns
+    /// let buffer = terminal.backend().buffer();
+    /// let (x, y) = (0..16u16)
+    ///     .flat_map(|y| (0..100u16).map(move |x| (x, y)))
+    ///     .find(|&(x, y)| buffer[(x, y)].symbol() == "┌" && buffer[(x, y + 1)].symbol() == "┘")
+    ///     .unwrap();
+    ///
+    /// // Select from 10ns to 29ns on the waveform
+    /// let mouse = |kind, column| MouseEvent {
+    ///     kind,
+    ///     column,
+    ///     row: y,
+    ///     modifiers: KeyModifiers::NONE,
+    /// };
+    /// app.handle_mouse_event(mouse(Down(MouseButton::Left), x));
+    /// app.handle_mouse_event(mouse(Drag(MouseButton::Left), x + 19));
+    /// let status = |app: &mut App, terminal: &mut Terminal<TestBackend>| {
+    ///     terminal.draw(|frame| app.draw(frame)).unwrap();
+    ///     let buffer = terminal.backend().buffer();
+    ///     buffer.content().iter().map(|x| x.symbol()).collect::<String>()
+    /// };
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 1ns | Window: [0fs, "));
+    ///
+    /// app.handle_mouse_event(mouse(Up(MouseButton::Left), x + 19));
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 500ps | Window: [10ns, "));
+    ///
+    /// app.handle_key_event(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE)).unwrap();
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 1ns | Window: [0fs, "));
+    ///
+    /// // Zooming in keeps 10ns under the pointer
+    /// app.handle_mouse_event(mouse(ScrollUp, x));
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 500ps | Window: [5ns, "));
+    /// app.handle_mouse_event(mouse(ScrollDown, x));
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 1ns | Window: [0fs, "));
+    ///
+    /// // Shift scrolls by a quarter of the window
+    /// let shift = |kind| MouseEvent { modifiers: KeyModifiers::SHIFT, ..mouse(kind, x) };
+    /// app.handle_mouse_event(shift(ScrollDown));
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 1ns | Window: [21ns, "));
+    /// app.handle_mouse_event(shift(ScrollUp));
+    /// assert!(status(&mut app, &mut terminal).contains("Step: 1ns | Window: [0fs, "));
+    /// ```
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if self.mode != AppMode::Run {
+            return;
+        }
+        let before = self.view_state();
+        self.handle_mouse(mouse_event);
+        if self.view_state() != before {
+            self.history.record(before);
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent) {
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        match mouse_event.kind {
+            // Clicking a signal focuses it, clicking its waveform also moves the cursor and starts
+            // selecting a time range
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.selection = None;
+                if let Some((index, (_, wave_area))) =
+                    self.signal_areas
+                        .iter()
+                        .enumerate()
+                        .find(|(_, (name_area, wave_area))| {
+                            name_area.contains(position) || wave_area.contains(position)
+                        })
+                {
+                    self.focus = index;
+                    if wave_area.contains(position) {
+                        self.cursor = (position.x - wave_area.x) as usize;
+                        self.selection = Some(self.cursor);
+                    }
+                }
+            }
+            // Releasing after dragging zooms so that the selected range fills the view
+            MouseEventKind::Up(MouseButton::Left) => {
+                if let Some((first, last)) = self.selected_columns().filter(|(a, b)| a != b) {
+                    let step = self.time_step.time();
+                    let span = ((last - first + 1) as u64).saturating_mul(step);
+                    self.time_start
+                        .increase((first as u64).saturating_mul(step));
+                    self.time_step = Time::fit_step(span, self.arr_size);
+                    self.cursor = 0;
+                }
+                self.selection = None;
+            }
+            // Right-clicking a waveform focuses the signal and copies its value under the pointer
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some((index, (_, wave_area))) = self
+                    .signal_areas
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (_, wave_area))| wave_area.contains(position))
+                {
+                    self.focus = index;
+                    self.cursor = (position.x - wave_area.x) as usize;
+                    self.copy_cursor_value();
+                }
+            }
+            // Shift with the wheel scrolls by a quarter of the window, as do horizontal wheels
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                if mouse_event.modifiers.contains(KeyModifiers::SHIFT) =>
+            {
+                self.pan(mouse_event.kind == MouseEventKind::ScrollDown);
+            }
+            MouseEventKind::ScrollLeft | MouseEventKind::ScrollRight => {
+                self.pan(mouse_event.kind == MouseEventKind::ScrollRight);
+            }
+            // The wheel zooms around the time under the pointer, or the cursor outside the
+            // waveforms
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                if let Some((_, wave_area)) = self
+                    .signal_areas
+                    .iter()
+                    .find(|(_, wave_area)| wave_area.contains(position))
+                {
+                    self.cursor = (position.x - wave_area.x) as usize;
+                }
+                match mouse_event.kind == MouseEventKind::ScrollUp {
+                    true => self.zoom_at_cursor(Time::step_decrease),
+                    false => self.zoom_at_cursor(Time::step_increase),
+                }
+            }
+            // Dragging moves the cursor along the waveform area, extending the selected range
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((_, wave_area)) = self.signal_areas.iter().find(|(_, x)| x.width > 0) {
+                    self.cursor = min(
+                        position.x.saturating_sub(wave_area.x) as usize,
+                        max(1, self.arr_size) - 1,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render the app into `frame`, e.g. a frame of a `TestBackend` terminal in tests
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::{app::App, command::Command, time::Time};
+    /// use ratatui::{Terminal, backend::TestBackend, style::Modifier};
+    /// use std::str::FromStr;
+    ///
+    /// let mut app = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// // Close the signal picker shown at startup
+    /// app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).unwrap();
+    /// app.run_command(Command::Add("test_tb:clk".to_string()));
+    /// app.run_command(Command::Step(Time::from_str("1ns").unwrap()));
+    ///
+    /// let mut terminal = Terminal::new(TestBackend::new(100, 16)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// // The cell where `text` starts, wherever the layout puts it
+    /// let find = |text: &str| {
+    ///     let cells: Vec<String> = text.chars().map(String::from).collect();
+    ///     (0..16u16).flat_map(|y| (0..100u16).map(move |x| (x, y))).find(|&(x, y)| {
+    ///         (0..cells.len() as u16)
+    ///             .all(|i| x + i < 100 && buffer[(x + i, y)].symbol() == cells[i as usize])
+    ///     })
+    /// };
+    ///
+    /// // Each label of the ruler ends above its tick, every 10ns
+    /// let (x, ruler) = find("10ns").unwrap();
+    /// let tick_10ns = x + 3;
+    /// assert_eq!(buffer[(tick_10ns, ruler + 1)].symbol(), "|");
+    /// assert_eq!(buffer[(tick_10ns + 10, ruler + 1)].symbol(), "|");
+    /// assert_eq!(find("20ns"), Some((tick_10ns + 7, ruler)));
+    /// // clk rises at 10ns, under the tick with a step of 1ns
+    /// let (x, high) = (ruler + 2..16)
+    ///     .flat_map(|y| (0..100u16).map(move |x| (x, y)))
+    ///     .find(|&xy| buffer[xy].symbol() == "┌")
+    ///     .unwrap();
+    /// assert_eq!(x, tick_10ns);
+    /// assert_eq!(buffer[(x, high + 1)].symbol(), "┘");
+    /// // Edges stand out from the holds
+    /// assert!(buffer[(x, high)].modifier.contains(Modifier::BOLD));
+    /// assert!(!buffer[(x - 1, high + 1)].modifier.contains(Modifier::BOLD));
+    /// assert!(find("Step: 1ns").is_some());
+    ///
+    /// // The ruler counts from the origin put at the cursor
+    /// for _ in 0..10 {
+    ///     app.handle_key_event(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE)).unwrap();
+    /// }
+    /// app.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// let row = |y: u16| (0..100).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+    /// let ruler = row(ruler);
+    /// assert!(ruler.find("-10ns").unwrap() < ruler.find(" 0fs").unwrap());
+    ///
+    /// // Past its last event, a signal shows the value it settles to
+    /// app.run_command(Command::Add("test_tb:rst".to_string()));
+    /// app.run_command(Command::Goto(Time::from_str("2us").unwrap()));
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// let screen: String = buffer.content().iter().map(|x| x.symbol()).collect();
+    /// assert!(screen.contains("Settled: 0"));
+    ///
+    /// // A terminal without room for the waveforms only shows a message
+    /// let mut terminal = Terminal::new(TestBackend::new(20, 3)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// let screen: String = buffer.content().iter().map(|x| x.symbol()).collect();
+    /// assert!(screen.contains("Terminal too small"));
+    /// let mut terminal = Terminal::new(TestBackend::new(1, 1)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// assert_eq!(terminal.backend().buffer()[(0, 0)].symbol(), "T");
+    /// ```
+    pub fn draw(&mut self, frame: &mut ratatui::Frame<'_>) {
         let main_layouts = Layout::default()
             .direction(Direction::Vertical)
             .margin(2)
-            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
             .split(frame.area());
 
         let name_stamp_layouts = Layout::default()
@@ -110,16 +1007,28 @@ impl<'a> App<'a> {
             .constraints([Constraint::Fill(1), Constraint::Fill(9)].as_ref())
             .split(main_layouts[0]);
 
-        let signal_layouts = Layout::default()
+        let mut row_constraints: Vec<Constraint> = self
+            .displayed_items
+            .iter()
+            .map(|item| match item {
+                DisplayItem::Signal(_) if self.compact => Constraint::Length(1),
+                // The comparison row adds a line
+                DisplayItem::Signal(displayed) => Constraint::Max(
+                    displayed.height + self.compared(&displayed.signal).is_some() as u16,
+                ),
+                DisplayItem::Divider(_) => Constraint::Length(1),
+            })
+            .collect();
+        // If there is no row, the layout below would be empty, so adding a row here
+        if row_constraints.is_empty() {
+            row_constraints.push(Constraint::Max(DEFAULT_HEIGHT));
+        }
+        let row_layouts = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![
-                Constraint::Max(4);
-                // FIXME: if displayed_signals = 0, it will crash, so adding a max here
-                max(1, self.displayed_signals.len())
-            ])
+            .constraints(row_constraints)
             .split(main_layouts[1]);
 
-        let signal_layouts: Vec<Rc<[Rect]>> = signal_layouts
+        let signal_layouts: Vec<Rc<[Rect]>> = row_layouts
             .iter()
             .map(|&x| {
                 Layout::default()
@@ -129,111 +1038,288 @@ impl<'a> App<'a> {
             })
             .collect();
 
-        self.arr_size = signal_layouts[0][1].width as usize;
+        // A terminal too small for a single column of waveform keeps the last view, so that the
+        // window never has zero columns
+        let wave_width = signal_layouts.first().map_or(0, |x| x[1].width);
+        if wave_width == 0 || main_layouts[1].height == 0 {
+            self.signal_areas.clear();
+            let message = Paragraph::new("Terminal too small").wrap(Wrap { trim: true });
+            frame.render_widget(message, frame.area());
+            return;
+        }
+        self.arr_size = wave_width as usize;
+        self.cursor = min(self.cursor, max(1, self.arr_size) - 1);
+
+        // Load the events of the visible window for the files loaded windowed
+        let time_end = self
+            .time_start
+            .time()
+            .saturating_add((self.arr_size as u64).saturating_mul(self.time_step.time()));
+        for (tag, waveform) in self.waveforms.iter_mut() {
+            if let Err(e) = waveform.load_window(self.time_start.time(), time_end) {
+                self.status_message = Some(format!("Failed to load {}: {}", tag, e));
+            }
+        }
 
         // Display program title
         let redundant = Paragraph::new(Line::from("RataWave").centered())
-            .block(Block::default().borders(Borders::ALL));
+            .block(self.block().borders(Borders::ALL));
         frame.render_widget(redundant, name_stamp_layouts[0]);
 
-        // Display time stamp
-        let mut time_stamp_str = String::from("");
-        // Show stamps after each 10 steps
-        let show_split = 10;
-        let mut time_stamp_graph = String::from("");
-        let mut stamp_index = 0;
-        while stamp_index < self.arr_size {
-            let mut time_stamp = format!(
-                "{}",
-                self.time_start.clone() + stamp_index as u64 * self.time_step.time()
-            );
-            let strip_len = min(10, self.arr_size - stamp_index);
-            if time_stamp.len() > strip_len {
-                time_stamp = time_stamp[0..strip_len].to_string();
-            } else {
-                time_stamp.push_str(" ".repeat(strip_len - time_stamp.len()).as_str());
-            }
-            time_stamp_str.push_str(&time_stamp);
-
-            time_stamp_graph.push_str(format!("|{}", " ".repeat(strip_len - 1)).as_str());
-
-            stamp_index += show_split;
-        }
-
+        // Display time ruler
+        let (tick_labels, ticks) = ruler_with_spacing(
+            self.time_start.time(),
+            self.time_step.time(),
+            self.arr_size,
+            self.tick_spacing,
+            self.time_origin,
+        );
+        let grid_columns: Vec<usize> = match self.show_grid {
+            true => ticks.match_indices('|').map(|(column, _)| column).collect(),
+            false => vec![],
+        };
         let time_show = Paragraph::new(vec![
-            Line::from(""),
-            Line::from(time_stamp_str),
-            Line::from(time_stamp_graph),
+            Line::styled(
+                bookmark_line(
+                    &self.bookmarks,
+                    self.time_start.time(),
+                    self.time_step.time(),
+                    self.arr_size,
+                    self.glyphs,
+                ),
+                Style::default().fg(self.theme.accent),
+            ),
+            Line::from(tick_labels),
+            Line::from(ticks),
         ]);
 
         frame.render_widget(time_show, name_stamp_layouts[1]);
 
-        // Display signals
-        for (index, signal) in self.displayed_signals.iter().enumerate() {
-            let signal = signal.borrow();
-            let mut signal_event_lines = self.get_lines_from_a_signal(&signal);
-            signal_event_lines.insert(0, Line::from(self.get_value_string_from_a_signal(&signal)));
+        // Display signals and dividers
+        for (index, item) in self.displayed_items.iter().enumerate() {
+            let displayed = match item {
+                DisplayItem::Signal(displayed) => displayed,
+                DisplayItem::Divider(label) => {
+                    let mut style = Style::default().fg(self.theme.accent);
+                    if index == self.focus {
+                        style = style.add_modifier(Modifier::BOLD).bg(self.theme.focus);
+                    }
+                    let width = row_layouts[index].width as usize;
+                    let line = self.glyphs.line;
+                    let text = format!("{} {} {}", line.repeat(2), label, line.repeat(width));
+                    frame.render_widget(Line::styled(text, style), row_layouts[index]);
+                    continue;
+                }
+            };
+            let signal = displayed.signal.borrow();
+            let mut signal_event_lines = self.get_lines_from_a_signal(&signal, displayed);
+            if self.compact {
+                signal_event_lines = vec![self.compact_line(&signal, signal_event_lines)];
+            } else {
+                signal_event_lines
+                    .insert(0, Line::from(self.get_value_string_from_a_signal(&signal)));
+            }
 
-            let signal_graph = Paragraph::new(signal_event_lines);
+            // Columns where the value searched with `:find` is reached
+            let match_columns: Vec<usize> = match &self.search {
+                Some(pattern) if index == self.focus => (0..self.arr_size)
+                    .filter(|column| {
+                        let (start, end) = self.column_range(*column);
+                        signal
+                            .events_between(start, end)
+                            .any(|(_, value)| pattern.matches(value, signal.width as usize))
+                    })
+                    .collect(),
+                _ => vec![],
+            };
 
-            let signal_name = Line::from(
-                self.displayed_signals
-                    .get(index)
-                    .unwrap()
-                    .borrow()
-                    .output_name(),
-            );
+            // Columns where the signal differs from the same signal of another file, shown in a
+            // comparison row below the waveform, or on the waveform itself in the compact mode
+            let compared = self.compared(&displayed.signal);
+            let differs: Vec<bool> = match &compared {
+                Some(other) => (0..self.arr_size)
+                    .map(|column| {
+                        let (start, end) = self.column_range(column);
+                        signal.differs_between(&other.borrow(), start, end)
+                    })
+                    .collect(),
+                None => vec![],
+            };
+            let diff_columns: Vec<usize> = match self.compact {
+                true => (0..differs.len()).filter(|x| differs[*x]).collect(),
+                false => vec![],
+            };
+            if compared.is_some() && !self.compact {
+                let style = Style::default()
+                    .bg(self.theme.error)
+                    .add_modifier(Modifier::BOLD);
+                let spans = differs.iter().map(|x| match x {
+                    true => Span::styled(self.glyphs.differs, style),
+                    false => Span::raw(" "),
+                });
+                signal_event_lines.push(Line::from_iter(spans));
+            }
+
+            let value_lines = if self.compact { 0 } else { 1 };
+            signal_event_lines
+                .iter_mut()
+                .skip(value_lines)
+                .for_each(|line| {
+                    draw_grid(line, &grid_columns, self.glyphs.grid, self.theme.grid);
+                    for (columns, color) in [
+                        (&match_columns, self.theme.accent),
+                        (&diff_columns, self.theme.error),
+                    ] {
+                        for column in columns {
+                            if let Some(span) = line.spans.get_mut(*column) {
+                                span.style = span.style.bg(color);
+                            }
+                        }
+                    }
+                    if let Some((first, last)) = self.selected_columns() {
+                        line.spans
+                            .iter_mut()
+                            .take(last + 1)
+                            .skip(first)
+                            .for_each(|span| {
+                                span.style = span.style.add_modifier(Modifier::REVERSED)
+                            });
+                    }
+                    highlight_column(line, self.cursor);
+                });
+
+            let mut signal_graph = Paragraph::new(signal_event_lines);
+
+            // The full path tells signals of the same name apart, prefixed by the file tag when
+            // several files are loaded
+            let name = match (&displayed.alias, self.signal_path(&displayed.signal)) {
+                (None, Some(path)) => path.clone(),
+                _ => displayed.output_name(),
+            };
+            let width = signal_layouts[index][0].width as usize;
+            let name = match displayed.is_vector() {
+                true => {
+                    let suffix = format!(" [{}]", displayed.mode.name());
+                    let width = width.saturating_sub(suffix.chars().count());
+                    elide_middle(&name, width, self.glyphs) + &suffix
+                }
+                false => elide_middle(&name, width, self.glyphs),
+            };
+            let mut name_lines = vec![Line::from(name)];
+            if index == self.focus {
+                name_lines[0] = name_lines[0].clone().style(Modifier::BOLD);
+            }
+            // Type of the signal and its activity over the view
+            // A regular clock shows its period and frequency instead
+            let activity = match self.clock_period(&signal) {
+                Some(period) => {
+                    let period = Time::new(period, TimescaleUnit::FS);
+                    let frequency = period.frequency().unwrap_or_default();
+                    format!("{}, clock {} {}", signal.var_type, period, frequency)
+                }
+                None => {
+                    let count = signal.transition_count_in_range(self.time_start.time(), time_end);
+                    format!("{}, {} transitions", signal.var_type, count)
+                }
+            };
+            name_lines.push(Line::styled(
+                activity,
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+            if let Some(delta) = self.marker_value_delta(&signal) {
+                name_lines.push(Line::from(delta));
+            }
+            // Past the last event, the value the signal settles to
+            if let Some((time, _)) = signal.events.last()
+                && *time < self.time_start.time()
+                && let Some(value) = signal.last_value()
+            {
+                let settled = format!("Settled: {}", displayed.format_value(value));
+                name_lines.push(Line::from(elide_middle(&settled, width, self.glyphs)));
+            }
+            name_lines.truncate(if self.compact { 1 } else { name_lines.len() });
+            let mut signal_name = Paragraph::new(name_lines);
+            if index == self.focus {
+                let style = Style::default().bg(self.theme.focus);
+                signal_name = signal_name.style(style);
+                signal_graph = signal_graph.style(style);
+            }
 
             frame.render_widget(signal_name, signal_layouts[index][0]);
             frame.render_widget(signal_graph, signal_layouts[index][1]);
+
+            // Arrows on the edges of the row when the signal changes outside the view
+            let area = signal_layouts[index][1];
+            let (before, after) = signal.events_outside(self.time_start.time(), time_end);
+            let style = Style::default().fg(self.theme.accent);
+            let y = area.y + value_lines as u16;
+            if before && area.width > 0 && area.height > value_lines as u16 {
+                let arrow = Rect::new(area.x, y, 1, 1);
+                frame.render_widget(Span::styled(self.glyphs.before, style), arrow);
+            }
+            if after && area.width > 0 && area.height > value_lines as u16 {
+                let arrow = Rect::new(area.right() - 1, y, 1, 1);
+                frame.render_widget(Span::styled(self.glyphs.after, style), arrow);
+            }
         }
+        self.signal_areas = self
+            .displayed_items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| match item {
+                DisplayItem::Signal(_) => (signal_layouts[index][0], signal_layouts[index][1]),
+                DisplayItem::Divider(_) => (row_layouts[index], Rect::default()),
+            })
+            .collect();
 
-        if self.mode == AppMode::Input {
-            let color_green = (*catppuccin::PALETTE
-                .mocha
-                .get_color(catppuccin::ColorName::Green))
-            .into();
-            let color_red = (*catppuccin::PALETTE
-                .mocha
-                .get_color(catppuccin::ColorName::Red))
-            .into();
+        // Display status line
+        frame.render_widget(Line::from(self.status_line()), main_layouts[2]);
 
-            let color_text = (*catppuccin::PALETTE
-                .mocha
-                .get_color(catppuccin::ColorName::Text))
-            .into();
+        if self.mode == AppMode::Input || self.mode == AppMode::Command {
+            let color_green = self.theme.valid;
+            let color_red = self.theme.error;
+            let color_text = self.theme.text;
 
             let input = &self.textarea.lines()[0];
 
-            match Time::is_valid(input) {
+            let (prompt, validity) = match self.mode {
+                AppMode::Command => (
+                    "Enter a command (goto, add, radix, remove, step, map, find)",
+                    Command::from_str(input)
+                        .map(|_| ())
+                        .map_err(|e| e.message().to_string()),
+                ),
+                _ => (
+                    self.input_target.prompt(),
+                    self.input_target.parse(input).map(|_| ()),
+                ),
+            };
+            match validity {
                 Ok(_) => {
                     self.textarea.set_style(Style::default().fg(color_green));
                     self.textarea.set_block(
-                        Block::default()
+                        self.block()
                             .border_style(color_green)
                             .borders(Borders::ALL)
-                            .title("Enter a time (e.g. 100ns) [Valid]"),
+                            .title(format!("{} [Valid]", prompt)),
                     );
                 }
                 Err(e) => {
                     if input.len() == 0 {
                         self.textarea.set_style(Style::default().fg(color_text));
                         self.textarea.set_block(
-                            Block::default()
+                            self.block()
                                 .border_style(color_text)
                                 .borders(Borders::ALL)
-                                .title(format!("Enter a time (e.g. 100ns)")),
+                                .title(prompt),
                         );
                     } else {
                         self.textarea.set_style(Style::default().fg(color_red));
                         self.textarea.set_block(
-                            Block::default()
+                            self.block()
                                 .border_style(color_red)
                                 .borders(Borders::ALL)
-                                .title(format!(
-                                    "Enter a time (e.g. 100ns) [Invalid: {}]",
-                                    e.message()
-                                )),
+                                .title(format!("{} [Invalid: {}]", prompt, e)),
                         );
                     }
                 }
@@ -252,85 +1338,704 @@ impl<'a> App<'a> {
             let [area] = horizontal.areas(area);
             frame.render_widget(widgets::Clear, area); //this clears out the background
 
-            let undisplayed_signals: Vec<Span> = self
-                .undisplayed_signals
-                .iter()
+            let [filter_area, area] =
+                Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+            self.filter.set_block(
+                self.block()
+                    .borders(Borders::ALL)
+                    .border_style(if self.filter_editing {
+                        Style::default().fg(self.theme.accent)
+                    } else {
+                        Style::default()
+                    })
+                    .title("Filter, press '/' to edit"),
+            );
+            frame.render_widget(&self.filter, filter_area);
+
+            // The rows change when signals are added or the filter is edited
+            let rows = self.picker_rows();
+            self.choice_index = min(self.choice_index, rows.len().saturating_sub(1));
+            let undisplayed_signals: Vec<Span> = rows
+                .into_iter()
                 .enumerate()
-                .map(|(i, x)| {
+                .map(|(i, row)| {
+                    let text = match row {
+                        PickerRow::Module(path, name, indent) => format!(
+                            "{}{} {}",
+                            "  ".repeat(indent),
+                            if self.collapsed.contains(&path) {
+                                self.glyphs.collapsed
+                            } else {
+                                self.glyphs.expanded
+                            },
+                            name
+                        ),
+                        PickerRow::Signal(signal, indent, _) => {
+                            format!("{}{}", "  ".repeat(indent), signal.borrow().output_name())
+                        }
+                    };
                     Span::styled(
-                        x.borrow().output_path().clone(),
+                        text,
                         if i == self.choice_index {
-                            Style::default().fg(Color::Blue)
+                            Style::default().fg(self.theme.accent)
                         } else {
                             Style::default()
                         },
                     )
                 })
                 .collect();
-            let lines: Vec<Line> = undisplayed_signals
+            let mut lines: Vec<Line> = undisplayed_signals
                 .iter()
                 .map(|x| Line::from(x.clone()))
                 .collect();
+            if lines.is_empty() {
+                let message = match self.filter.lines()[0].is_empty() {
+                    true => "All signals are displayed",
+                    false => "No matches",
+                };
+                lines.push(Line::styled(
+                    message,
+                    Style::default().add_modifier(Modifier::DIM),
+                ));
+            }
+            let par = Paragraph::new(lines).block(self.block().borders(Borders::ALL).title_top(
+                "Add signals, Enter to add or fold, Space to fold the module, 'q' to exit",
+            ));
+            frame.render_widget(par, area);
+        } else if self.mode == AppMode::Bookmarks {
+            let area = popup_area(frame.area(), 60, 60);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
+
+            let lines: Vec<Line> = self
+                .bookmarks
+                .iter()
+                .enumerate()
+                .map(|(i, (time, label))| {
+                    let text = format!("{:>12}  {}", Time::new(*time, TimescaleUnit::FS), label);
+                    match i == self.choice_index {
+                        true => Line::styled(text, Style::default().fg(self.theme.accent)),
+                        false => Line::from(text),
+                    }
+                })
+                .collect();
             let par = Paragraph::new(lines).block(
-                Block::default()
+                self.block()
                     .borders(Borders::ALL)
-                    .title_top("Add signals, press 'q' to exit"),
+                    .title_top("Bookmarks, Enter to jump, 'd' to delete, 'q' to exit"),
             );
             frame.render_widget(par, area);
-        }
-    }
+        } else if [
+            AppMode::AddDivider,
+            AppMode::ExportCsv,
+            AppMode::Rename,
+            AppMode::AddBookmark,
+        ]
+        .contains(&self.mode)
+        {
+            self.textarea
+                .set_block(self.block().borders(Borders::ALL).title(match self.mode {
+                    AppMode::AddDivider => "Enter the label of the divider",
+                    AppMode::Rename => "Enter the alias of the signal",
+                    AppMode::AddBookmark => "Enter the label of the bookmark",
+                    _ => "Enter the path of the CSV file",
+                }));
 
-    fn handle_key_event(&mut self, key_event: event::KeyEvent) -> io::Result<()> {
-        match self.mode {
-            AppMode::Run => match key_event.code {
-                KeyCode::Char('a') => {
-                    self.mode = AppMode::AddSignal;
-                    self.choice_index = 0;
-                }
-                KeyCode::Char('q') => {
-                    self.mode = AppMode::Exit;
-                }
-                KeyCode::Char('=') => {
-                    self.time_step.step_decrease();
-                }
-                KeyCode::Char('-') => {
-                    self.time_step.step_increase();
-                }
-                KeyCode::Char('h') => {
-                    self.time_start
-                        .decrease(self.arr_size as u64 / 2 * self.time_step.time());
-                }
-                KeyCode::Char('l') => {
-                    self.time_start
-                        .increase(self.arr_size as u64 / 2 * self.time_step.time());
-                }
-                KeyCode::Char('t') => {
-                    self.mode = AppMode::Input;
-                    // Initialize textarea
-                    self.textarea = TextArea::default();
-                }
-                _ => {}
-            },
+            let vertical = Layout::vertical([Constraint::Max(3)]).flex(Flex::Start);
+            let horizontal = Layout::horizontal([Constraint::Max(80)]).flex(Flex::Center);
+            let [area] = vertical.areas(frame.area());
+            let [area] = horizontal.areas(area);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
+            frame.render_widget(&self.textarea, area);
+        } else if self.mode == AppMode::RestoreSession {
+            let vertical = Layout::vertical([Constraint::Max(3)]).flex(Flex::Center);
+            let horizontal = Layout::horizontal([Constraint::Max(80)]).flex(Flex::Center);
+            let [area] = vertical.areas(frame.area());
+            let [area] = horizontal.areas(area);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
 
-            AppMode::Input => match key_event.code {
-                // When pressing Esc, directly return to the normal mode
-                KeyCode::Esc => {
-                    self.mode = AppMode::Run;
-                }
-                KeyCode::Enter => {
-                    if Time::is_valid(self.textarea.lines()[0].as_str()).is_ok() {
-                        self.mode = AppMode::Run;
-                        let text = self.textarea.lines(); // Get input text
-                        let text = text.first().unwrap();
-                        let time = Time::from_str(text).unwrap();
-                        self.time_start = time;
+            let par = Paragraph::new(Line::from(format!(
+                "Restore the session from {}? (y/n)",
+                Session::path_for(&self.file_paths[0]).display()
+            )))
+            .block(self.block().borders(Borders::ALL));
+            frame.render_widget(par, area);
+        } else if self.mode == AppMode::ConfirmClear {
+            let vertical = Layout::vertical([Constraint::Max(3)]).flex(Flex::Center);
+            let horizontal = Layout::horizontal([Constraint::Max(80)]).flex(Flex::Center);
+            let [area] = vertical.areas(frame.area());
+            let [area] = horizontal.areas(area);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
+
+            let par = Paragraph::new(Line::from(format!(
+                "Remove all {} displayed rows? (y/n)",
+                self.displayed_items.len()
+            )))
+            .block(self.block().borders(Borders::ALL));
+            frame.render_widget(par, area);
+        } else if self.mode == AppMode::Unknowns {
+            let area = popup_area(frame.area(), 60, 60);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
+
+            let lines: Vec<Line> = self
+                .unknowns
+                .iter()
+                .enumerate()
+                .map(|(i, (time, signal))| {
+                    let path = self
+                        .signal_path(signal)
+                        .cloned()
+                        .unwrap_or_else(|| signal.borrow().output_path());
+                    let text = format!("{:>12}  {}", Time::new(*time, TimescaleUnit::FS), path);
+                    match i == self.choice_index {
+                        true => Line::styled(text, Style::default().fg(self.theme.accent)),
+                        false => Line::from(text),
                     }
+                })
+                .collect();
+            // Keep the chosen entry in view
+            let height = area.height.saturating_sub(2) as usize;
+            let scroll = (self.choice_index + 1).saturating_sub(height) as u16;
+            let par = Paragraph::new(lines).scroll((scroll, 0)).block(
+                self.block()
+                    .borders(Borders::ALL)
+                    .title_top("x and z values, Enter to jump, 'q' to exit"),
+            );
+            frame.render_widget(par, area);
+        } else if self.mode == AppMode::Info {
+            let area = popup_area(frame.area(), 60, 60);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
+
+            let mut lines = vec![];
+            for (file_path, (_, waveform)) in self.file_paths.iter().zip(self.waveforms.iter()) {
+                let info = waveform.info();
+                lines.push(Line::styled(
+                    file_path.clone(),
+                    Style::default().fg(self.theme.accent),
+                ));
+                let unknown = || "-".to_string();
+                lines.push(Line::from(format!(
+                    "  Date:    {}",
+                    info.date.clone().unwrap_or_else(unknown)
+                )));
+                lines.push(Line::from(format!(
+                    "  Version: {}",
+                    info.version.clone().unwrap_or_else(unknown)
+                )));
+                for comment in info.comments.iter() {
+                    lines.push(Line::from(format!("  Comment: {}", comment)));
+                }
+            }
+            let par = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                self.block()
+                    .borders(Borders::ALL)
+                    .title_top("File info, press the info key or Esc to close"),
+            );
+            frame.render_widget(par, area);
+        } else if self.mode == AppMode::Help {
+            let area = popup_area(frame.area(), 60, 60);
+            frame.render_widget(widgets::Clear, area); //this clears out the background
+
+            // The keys of the key map, followed by the name of the action for the config file
+            let mut bindings: Vec<(String, String)> = KEY_BINDINGS
+                .iter()
+                .map(|(action, _, description)| {
+                    (
+                        self.keymap.keys_of(*action).join(" "),
+                        format!("{} ({})", description, action.name()),
+                    )
+                })
+                .collect();
+            bindings.push(("Mouse".to_string(), MOUSE_HELP.to_string()));
+            let key_width = bindings
+                .iter()
+                .map(|(keys, _)| keys.chars().count())
+                .max()
+                .unwrap_or(0);
+            let lines: Vec<Line> = bindings
+                .iter()
+                .map(|(keys, action)| Line::from(format!("{:<key_width$}  {}", keys, action)))
+                .collect();
+            let par = Paragraph::new(lines).block(
+                self.block()
+                    .borders(Borders::ALL)
+                    .title_top("Help, press the help key or Esc to close"),
+            );
+            frame.render_widget(par, area);
+        }
+    }
+
+    /// Handle a key press in the current mode
+    /// A key press changing the view or the displayed signals can be undone, except reloading
+    /// the files, which clears the history
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::app::App;
+    /// use ratatui::{Terminal, backend::TestBackend};
+    ///
+    /// let mut app = App::new(vec!["assets/verilog/test_1.vcd".to_string()]).unwrap();
+    /// let press = |app: &mut App, code| {
+    ///     app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE)).unwrap()
+    /// };
+    /// // Filter the signal picker shown at startup with a filter matching nothing
+    /// press(&mut app, KeyCode::Char('/'));
+    /// "nothing".chars().for_each(|c| press(&mut app, KeyCode::Char(c)));
+    /// press(&mut app, KeyCode::Enter);
+    /// for code in [KeyCode::Char('j'), KeyCode::Char('k'), KeyCode::Enter, KeyCode::Char(' ')] {
+    ///     press(&mut app, code);
+    /// }
+    ///
+    /// let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    /// terminal.draw(|frame| app.draw(frame)).unwrap();
+    /// let buffer = terminal.backend().buffer();
+    /// let screen: String = buffer.content().iter().map(|x| x.symbol()).collect();
+    /// assert!(screen.contains("No matches"));
+    ///
+    /// // Clearing the displayed signals asks first, and can be undone
+    /// press(&mut app, KeyCode::Esc);
+    /// press(&mut app, KeyCode::Esc);
+    /// app.run_command(rata_wave::command::Command::Add("test_tb:clk".to_string()));
+    /// let mut screen = |app: &mut App| {
+    ///     terminal.draw(|frame| app.draw(frame)).unwrap();
+    ///     let buffer = terminal.backend().buffer();
+    ///     buffer.content().iter().map(|x| x.symbol()).collect::<String>()
+    /// };
+    /// press(&mut app, KeyCode::Char('C'));
+    /// assert!(screen(&mut app).contains("Remove all 1 displayed rows? (y/n)"));
+    /// press(&mut app, KeyCode::Char('n'));
+    /// assert!(screen(&mut app).contains("test_…k"));
+    /// press(&mut app, KeyCode::Char('C'));
+    /// press(&mut app, KeyCode::Char('y'));
+    /// assert!(!screen(&mut app).contains("test_…k"));
+    /// press(&mut app, KeyCode::Char('u'));
+    /// assert!(screen(&mut app).contains("test_…k"));
+    /// ```
+    pub fn handle_key_event(&mut self, key_event: event::KeyEvent) -> io::Result<()> {
+        let undoing = self.mode == AppMode::Run
+            && matches!(
+                self.keymap.action(&key_event),
+                Some(Action::Undo | Action::Redo | Action::Reload)
+            );
+        let before = self.view_state();
+        self.handle_key(key_event)?;
+        if !undoing && self.view_state() != before {
+            self.history.record(before);
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key_event: event::KeyEvent) -> io::Result<()> {
+        self.status_message = None;
+        match self.mode {
+            AppMode::Run => match self.keymap.action(&key_event) {
+                Some(Action::Undo) => match self.history.undo(self.view_state()) {
+                    Some(state) => self.restore_view_state(state),
+                    None => self.status_message = Some("Nothing to undo".to_string()),
+                },
+                Some(Action::Redo) => match self.history.redo(self.view_state()) {
+                    Some(state) => self.restore_view_state(state),
+                    None => self.status_message = Some("Nothing to redo".to_string()),
+                },
+                Some(Action::Reload) => {
+                    self.status_message = Some(match self.reload() {
+                        Ok(0) => "Reloaded".to_string(),
+                        Ok(dropped) => {
+                            format!("Reloaded, {} signals no longer exist", dropped)
+                        }
+                        Err(e) => format!("Failed to reload: {}", e),
+                    });
+                }
+                Some(Action::AddBookmark) => {
+                    self.mode = AppMode::AddBookmark;
+                    self.textarea = TextArea::default();
+                }
+                Some(Action::Bookmarks) => {
+                    if self.bookmarks.is_empty() {
+                        self.status_message = Some("No bookmarks".to_string());
+                    } else {
+                        self.mode = AppMode::Bookmarks;
+                        self.choice_index = 0;
+                    }
+                }
+                Some(Action::AddSignal) => {
+                    self.mode = AppMode::AddSignal;
+                    self.choice_index = 0;
+                }
+                Some(Action::Quit) => {
+                    self.mode = AppMode::Exit;
+                }
+                Some(Action::ZoomIn) => {
+                    self.zoom_at_cursor(Time::step_decrease);
+                }
+                Some(Action::ZoomOut) => {
+                    self.zoom_at_cursor(Time::step_increase);
+                }
+                Some(Action::CopyValue) => {
+                    self.copy_cursor_value();
+                }
+                Some(Action::ZoomFit) => {
+                    self.time_start = Time::new(0, TimescaleUnit::FS);
+                    self.time_step = Time::fit_step(self.max_time, self.arr_size);
+                }
+                Some(Action::ScrollLeft) => {
+                    self.time_start
+                        .decrease((self.arr_size as u64 / 2).saturating_mul(self.time_step.time()));
+                }
+                Some(Action::ScrollRight) => {
+                    self.time_start
+                        .increase((self.arr_size as u64 / 2).saturating_mul(self.time_step.time()));
+                    self.clamp_time_start();
+                }
+                Some(action @ (Action::GotoTime | Action::CenterTime | Action::SetStep)) => {
+                    self.mode = AppMode::Input;
+                    self.input_target = match action {
+                        Action::CenterTime => InputTarget::Center,
+                        Action::SetStep => InputTarget::Step,
+                        _ => InputTarget::Start,
+                    };
+                    // Initialize textarea
+                    self.textarea = TextArea::default();
+                }
+                Some(Action::CursorLeft) => {
+                    self.cursor = max(1, self.cursor) - 1;
+                }
+                Some(Action::CursorRight) => {
+                    self.cursor = min(self.cursor + 1, max(1, self.arr_size) - 1);
+                }
+                Some(Action::MarkerA) => {
+                    self.marker_a = Some(self.cursor_time());
+                }
+                Some(Action::MarkerB) => {
+                    self.marker_b = Some(self.cursor_time());
+                }
+                Some(Action::Clear) => match self.displayed_items.is_empty() {
+                    true => self.status_message = Some("Nothing to clear".to_string()),
+                    false => self.mode = AppMode::ConfirmClear,
+                },
+                Some(Action::SetOrigin) => {
+                    self.time_origin = match self.cursor_time() == self.time_origin {
+                        true => 0,
+                        false => self.cursor_time(),
+                    };
+                }
+                Some(Action::FocusNext) => {
+                    self.focus = min(self.focus + 1, max(1, self.displayed_items.len()) - 1);
+                }
+                Some(Action::FocusPrev) => {
+                    self.focus = max(1, self.focus) - 1;
+                }
+                Some(Action::MoveDown) if self.focus + 1 < self.displayed_items.len() => {
+                    self.displayed_items.swap(self.focus, self.focus + 1);
+                    self.focus += 1;
+                }
+                Some(Action::MoveUp)
+                    if self.focus > 0 && self.focus < self.displayed_items.len() =>
+                {
+                    self.displayed_items.swap(self.focus - 1, self.focus);
+                    self.focus -= 1;
+                }
+                Some(Action::InsertDivider) => {
+                    self.mode = AppMode::AddDivider;
+                    self.textarea = TextArea::default();
+                }
+                Some(Action::NextEdge) if self.search.is_some() => self.jump_to_match(true),
+                Some(Action::PrevEdge) if self.search.is_some() => self.jump_to_match(false),
+                Some(Action::NextEdge) => {
+                    // Search from the end of the cursor column so that the edge under the
+                    // cursor is skipped
+                    let after = self.column_range(self.cursor).1 - 1;
+                    let edge = self
+                        .focused_signal()
+                        .and_then(|signal| signal.borrow().next_value_change(after));
+                    if let Some(edge) = edge {
+                        self.center_on(edge);
+                    }
+                }
+                Some(Action::PrevEdge) => {
+                    let edge = self
+                        .focused_signal()
+                        .and_then(|signal| signal.borrow().prev_value_change(self.cursor_time()));
+                    if let Some(edge) = edge {
+                        self.center_on(edge);
+                    }
+                }
+                // The same as `n` and `N`, over all displayed signals
+                Some(Action::NextChange) => {
+                    let after = self.column_range(self.cursor).1 - 1;
+                    let change = self
+                        .displayed_signals()
+                        .filter_map(|x| x.signal.borrow().next_value_change(after))
+                        .min();
+                    if let Some(change) = change {
+                        self.center_on(change);
+                    }
+                }
+                Some(Action::PrevChange) => {
+                    let change = self
+                        .displayed_signals()
+                        .filter_map(|x| x.signal.borrow().prev_value_change(self.cursor_time()))
+                        .max();
+                    if let Some(change) = change {
+                        self.center_on(change);
+                    }
+                }
+                Some(Action::Help) => {
+                    self.mode = AppMode::Help;
+                }
+                Some(Action::Info) => {
+                    self.mode = AppMode::Info;
+                }
+                Some(Action::Command) => {
+                    self.mode = AppMode::Command;
+                    self.textarea = TextArea::default();
+                }
+                Some(action @ (Action::Grow | Action::Shrink)) => {
+                    if let Some(DisplayItem::Signal(displayed)) =
+                        self.displayed_items.get_mut(self.focus)
+                    {
+                        displayed.resize(if action == Action::Grow { 1 } else { -1 });
+                    }
+                }
+                Some(Action::ToggleAnalog) => {
+                    if let Some(DisplayItem::Signal(displayed)) =
+                        self.displayed_items.get_mut(self.focus)
+                    {
+                        if displayed.is_vector() {
+                            displayed.mode = displayed.mode.toggle();
+                        } else {
+                            self.status_message =
+                                Some("Analog mode is only available for vectors".to_string());
+                        }
+                    }
+                }
+                Some(Action::CycleFormat) => {
+                    if let Some(DisplayItem::Signal(displayed)) =
+                        self.displayed_items.get_mut(self.focus)
+                    {
+                        if displayed.is_vector() {
+                            displayed.mode = displayed.mode.next();
+                        } else {
+                            self.status_message =
+                                Some("Formats are only available for vectors".to_string());
+                        }
+                    }
+                }
+                Some(Action::ToggleGrid) => {
+                    self.show_grid = !self.show_grid;
+                }
+                Some(Action::ToggleCompact) => {
+                    self.compact = !self.compact;
+                }
+                Some(Action::ToggleDiff) => {
+                    self.diff = !self.diff;
+                    self.first_differences.borrow_mut().clear();
+                    if self.diff && self.waveforms.len() < 2 {
+                        self.status_message =
+                            Some("Comparing needs two or more waveform files".to_string());
+                    }
+                }
+                Some(Action::NextTheme) => {
+                    self.theme = Theme::new(self.theme.flavor.next());
+                    self.status_message = Some(format!("Theme: {}", self.theme.flavor.name()));
+                }
+                Some(Action::ExportSvg) => {
+                    let path = Path::new(&self.file_paths[0]).with_file_name("ratawave.svg");
+                    self.status_message = Some(match self.export_svg(&path) {
+                        Ok(_) => format!("Exported the view to {}", path.display()),
+                        Err(e) => format!("Failed to export the view: {}", e),
+                    });
+                }
+                Some(Action::Rename) => {
+                    if let Some(DisplayItem::Signal(displayed)) =
+                        self.displayed_items.get(self.focus)
+                    {
+                        self.mode = AppMode::Rename;
+                        let alias = displayed.alias.clone().unwrap_or_default();
+                        self.textarea = TextArea::new(vec![alias]);
+                        self.textarea.move_cursor(tui_textarea::CursorMove::End);
+                    }
+                }
+                Some(Action::ExportCsv) => {
+                    self.mode = AppMode::ExportCsv;
+                    let path = Path::new(&self.file_paths[0]).with_file_name("ratawave.csv");
+                    self.textarea = TextArea::new(vec![path.display().to_string()]);
+                    self.textarea.move_cursor(tui_textarea::CursorMove::End);
+                }
+                Some(Action::SaveSession) => {
+                    let path = Session::path_for(&self.file_paths[0]);
+                    self.status_message = Some(match self.save_session(&path) {
+                        Ok(_) => format!("Saved session to {}", path.display()),
+                        Err(e) => format!("Failed to save session: {}", e),
+                    });
+                }
+                _ => {}
+            },
+
+            AppMode::Input => match key_event.code {
+                // When pressing Esc, directly return to the normal mode
+                KeyCode::Esc => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Enter => {
+                    if let Ok(time) = self.input_target.parse(&self.textarea.lines()[0]) {
+                        self.mode = AppMode::Run;
+                        match self.input_target {
+                            InputTarget::Start => self.time_start = time,
+                            InputTarget::Center => self.center_on(time.time()),
+                            InputTarget::Step => self.time_step = time,
+                        }
+                        self.clamp_time_start();
+                    }
+                }
+                _ => {
+                    self.textarea.input(key_event);
+                }
+            },
+            AppMode::Command => match key_event.code {
+                KeyCode::Esc => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Enter => {
+                    if let Ok(command) = Command::from_str(&self.textarea.lines()[0]) {
+                        self.mode = AppMode::Run;
+                        self.run_command(command);
+                    }
+                }
+                _ => {
+                    self.textarea.input(key_event);
+                }
+            },
+            AppMode::AddDivider => match key_event.code {
+                KeyCode::Esc => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Enter => {
+                    self.mode = AppMode::Run;
+                    let label = self.textarea.lines()[0].clone();
+                    // Insert below the focused row, or as the first row if nothing is displayed
+                    let index = min(self.focus + 1, self.displayed_items.len());
+                    self.displayed_items
+                        .insert(index, DisplayItem::Divider(label));
+                    self.focus = index;
+                }
+                _ => {
+                    self.textarea.input(key_event);
+                }
+            },
+            AppMode::Rename => match key_event.code {
+                KeyCode::Esc => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Enter => {
+                    self.mode = AppMode::Run;
+                    let alias = self.textarea.lines()[0].trim().to_string();
+                    if let Some(DisplayItem::Signal(displayed)) =
+                        self.displayed_items.get_mut(self.focus)
+                    {
+                        displayed.alias = (!alias.is_empty()).then_some(alias);
+                    }
+                }
+                _ => {
+                    self.textarea.input(key_event);
+                }
+            },
+            AppMode::AddBookmark => match key_event.code {
+                KeyCode::Esc => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Enter => {
+                    self.mode = AppMode::Run;
+                    let time = self.cursor_time();
+                    let label = match self.textarea.lines()[0].trim() {
+                        "" => Time::new(time, TimescaleUnit::FS).to_string(),
+                        label => label.to_string(),
+                    };
+                    // A bookmark replaces the one at the same time
+                    self.bookmarks.retain(|(t, _)| *t != time);
+                    let index = self.bookmarks.partition_point(|(t, _)| *t < time);
+                    self.bookmarks.insert(index, (time, label));
+                }
+                _ => {
+                    self.textarea.input(key_event);
+                }
+            },
+            AppMode::Bookmarks => match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.choice_index = min(self.choice_index + 1, self.bookmarks.len() - 1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.choice_index = max(1, self.choice_index) - 1;
+                }
+                KeyCode::Enter => {
+                    self.mode = AppMode::Run;
+                    self.center_on(self.bookmarks[self.choice_index].0);
+                    self.clamp_time_start();
+                }
+                KeyCode::Char('d') => {
+                    self.bookmarks.remove(self.choice_index);
+                    if self.bookmarks.is_empty() {
+                        self.mode = AppMode::Run;
+                    }
+                    self.choice_index = min(self.choice_index, max(1, self.bookmarks.len()) - 1);
+                }
+                _ => {}
+            },
+            AppMode::Unknowns => match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.choice_index = min(self.choice_index + 1, self.unknowns.len() - 1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.choice_index = max(1, self.choice_index) - 1;
+                }
+                // Jump to the value, focusing its signal if it is displayed
+                KeyCode::Enter => {
+                    self.mode = AppMode::Run;
+                    let (time, signal) = &self.unknowns[self.choice_index];
+                    if let Some(index) = self.displayed_items.iter().position(
+                        |item| matches!(item, DisplayItem::Signal(x) if Rc::ptr_eq(&x.signal, signal)),
+                    ) {
+                        self.focus = index;
+                    }
+                    self.center_on(*time);
+                    self.clamp_time_start();
+                }
+                _ => {}
+            },
+            AppMode::ExportCsv => match key_event.code {
+                KeyCode::Esc => {
+                    self.mode = AppMode::Run;
+                }
+                KeyCode::Enter => {
+                    self.mode = AppMode::Run;
+                    let path = self.textarea.lines()[0].clone();
+                    self.status_message = Some(match self.export_csv(Path::new(&path)) {
+                        Ok(_) => format!("Exported the signals to {}", path),
+                        Err(e) => format!("Failed to export the signals: {}", e),
+                    });
+                }
+                _ => {
+                    self.textarea.input(key_event);
+                }
+            },
+            AppMode::AddSignal if self.filter_editing => match key_event.code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    self.filter_editing = false;
+                }
+                _ => {
+                    self.filter.input(key_event);
+                    self.choice_index = 0;
                 }
-                _ => {
-                    self.textarea.input(key_event);
-                }
             },
             AppMode::AddSignal => match key_event.code {
+                KeyCode::Char('/') => {
+                    self.filter_editing = true;
+                }
                 KeyCode::Esc => {
                     self.mode = AppMode::Run;
                 }
@@ -339,120 +2044,772 @@ impl<'a> App<'a> {
                 }
                 KeyCode::Char('j') => {
                     self.choice_index += 1;
-                    self.choice_index = min(self.choice_index, self.undisplayed_signals.len() - 1);
+                    self.choice_index =
+                        min(self.choice_index, max(1, self.picker_rows().len()) - 1);
                 }
                 KeyCode::Char('k') => {
                     self.choice_index = max(1, self.choice_index) - 1;
                 }
-                KeyCode::Enter => {
-                    self.displayed_signals.push(Rc::clone(
-                        self.undisplayed_signals.get(self.choice_index).unwrap(),
-                    ));
-                    self.undisplayed_signals.remove(self.choice_index);
-                    if self.undisplayed_signals.len() > 0 {
+                KeyCode::Enter => match self.picker_rows().into_iter().nth(self.choice_index) {
+                    Some(PickerRow::Module(path, _, _)) => self.toggle_collapsed(path),
+                    Some(PickerRow::Signal(signal, _, _)) => {
+                        self.displayed_items
+                            .push(DisplayItem::Signal(DisplayedSignal::new(signal)));
                         self.choice_index =
-                            min(self.choice_index, self.undisplayed_signals.len() - 1)
+                            min(self.choice_index, max(1, self.picker_rows().len()) - 1);
                     }
+                    None => {}
+                },
+                KeyCode::Char(' ') => {
+                    let path = match self.picker_rows().into_iter().nth(self.choice_index) {
+                        Some(PickerRow::Module(path, _, _)) => Some(path),
+                        Some(PickerRow::Signal(_, _, parent)) => parent,
+                        None => None,
+                    };
+                    if let Some(path) = path {
+                        self.toggle_collapsed(path);
+                    }
+                }
+                _ => {}
+            },
+            AppMode::RestoreSession => match key_event.code {
+                KeyCode::Char('y') => {
+                    self.mode = AppMode::Run;
+                    let path = Session::path_for(&self.file_paths[0]);
+                    self.status_message = Some(match self.load_session(&path) {
+                        Ok(0) => format!("Restored session from {}", path.display()),
+                        Ok(missing) => format!(
+                            "Restored session from {}, {} signal(s) not found",
+                            path.display(),
+                            missing
+                        ),
+                        Err(e) => format!("Failed to restore session: {}", e),
+                    });
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.mode = AppMode::AddSignal;
+                }
+                _ => {}
+            },
+            AppMode::ConfirmClear => match key_event.code {
+                KeyCode::Char('y') => {
+                    self.mode = AppMode::Run;
+                    self.displayed_items.clear();
+                    self.focus = 0;
+                    self.selection = None;
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.mode = AppMode::Run;
                 }
                 _ => {}
             },
+            AppMode::Help
+                if key_event.code == KeyCode::Esc
+                    || self.keymap.action(&key_event) == Some(Action::Help) =>
+            {
+                self.mode = AppMode::Run;
+            }
+            AppMode::Info
+                if key_event.code == KeyCode::Esc
+                    || self.keymap.action(&key_event) == Some(Action::Info) =>
+            {
+                self.mode = AppMode::Run;
+            }
             _ => {}
         }
         Ok(())
     }
 
+    /// Copy the value of the focused signal at the cursor to the clipboard, in its format
+    fn copy_cursor_value(&mut self) {
+        let value = match self.displayed_items.get(self.focus) {
+            Some(DisplayItem::Signal(displayed)) => displayed.value_at(self.cursor_time()),
+            _ => {
+                self.status_message = Some("The focused row is not a signal".to_string());
+                return;
+            }
+        };
+        let Some(value) = value else {
+            self.status_message = Some("No value at the cursor".to_string());
+            return;
+        };
+
+        #[cfg(feature = "clipboard")]
+        let result = crate::utils::clipboard::copy(&value);
+        #[cfg(not(feature = "clipboard"))]
+        let result: io::Result<()> = Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "copying requires the `clipboard` feature",
+        ));
+        self.status_message = Some(match result {
+            Ok(()) => format!("Copied {}", value),
+            Err(e) => format!("Failed to copy {}: {}", value, e),
+        });
+    }
+
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            items: self
+                .displayed_items
+                .iter()
+                .map(|item| match item {
+                    DisplayItem::Signal(displayed) => ItemState::Signal(
+                        Rc::downgrade(&displayed.signal),
+                        displayed.mode,
+                        displayed.alias.clone(),
+                        displayed.value_map.clone(),
+                        displayed.height,
+                    ),
+                    DisplayItem::Divider(label) => ItemState::Divider(label.clone()),
+                })
+                .collect(),
+            time_start: self.time_start.clone(),
+            time_step: self.time_step.clone(),
+        }
+    }
+
+    /// Restore a snapshot of the view, signals dropped since then (e.g. by a reload) are left out
+    fn restore_view_state(&mut self, state: ViewState) {
+        self.displayed_items = state
+            .items
+            .into_iter()
+            .filter_map(|item| match item {
+                ItemState::Signal(signal, mode, alias, value_map, height) => {
+                    Some(DisplayItem::Signal(DisplayedSignal {
+                        signal: signal.upgrade()?,
+                        mode,
+                        alias,
+                        value_map,
+                        height,
+                    }))
+                }
+                ItemState::Divider(label) => Some(DisplayItem::Divider(label)),
+            })
+            .collect();
+        self.time_start = state.time_start;
+        self.time_step = state.time_step;
+        self.focus = min(self.focus, max(1, self.displayed_items.len()) - 1);
+    }
+
+    /// Time at the column of the measurement cursor
+    fn cursor_time(&self) -> u64 {
+        self.column_range(self.cursor).0
+    }
+
+    /// Start and end times of a column of the view, saturating at the end of time
+    fn column_range(&self, column: usize) -> (u64, u64) {
+        let step = self.time_step.time();
+        let start = self
+            .time_start
+            .time()
+            .saturating_add((column as u64).saturating_mul(step));
+        (start, start.saturating_add(step))
+    }
+
+    /// Signals not displayed yet with their paths, in hierarchy order
+    fn undisplayed_signals(&self) -> impl Iterator<Item = &(String, Rc<RefCell<Signal>>)> {
+        self.signals.iter().filter(|(_, signal)| {
+            !self
+                .displayed_signals()
+                .any(|x| Rc::ptr_eq(&x.signal, signal))
+        })
+    }
+
+    /// Signals that can be added, i.e. not displayed and matching the filter (case-insensitive)
+    fn signal_candidates(&self) -> Vec<(String, Rc<RefCell<Signal>>)> {
+        let filter = self.filter.lines()[0].to_lowercase();
+        self.undisplayed_signals()
+            .filter(|(path, _)| path.to_lowercase().contains(&filter))
+            .cloned()
+            .collect()
+    }
+
+    /// Rows of the add-signal picker, i.e. the candidate signals under their module headers
+    /// Modules without candidates are hidden, and collapsed modules hide their contents
+    fn picker_rows(&self) -> Vec<PickerRow> {
+        let candidates: HashSet<*const RefCell<Signal>> = self
+            .signal_candidates()
+            .iter()
+            .map(|(_, signal)| Rc::as_ptr(signal))
+            .collect();
+        if let [(_, waveform)] = self.waveforms.as_slice() {
+            let root = waveform.root();
+            return collect_picker_rows(&root, "", 0, None, &candidates, &self.collapsed);
+        }
+
+        // With several files, the modules of each file are listed under the tag of the file
+        let mut rows = vec![];
+        for (tag, waveform) in &self.waveforms {
+            let prefix = format!("{}:", tag);
+            let root = waveform.root();
+            let file_rows =
+                collect_picker_rows(&root, &prefix, 1, Some(tag), &candidates, &self.collapsed);
+            if file_rows.is_empty() {
+                continue;
+            }
+            rows.push(PickerRow::Module(tag.clone(), tag.clone(), 0));
+            if !self.collapsed.contains(tag) {
+                rows.extend(file_rows);
+            }
+        }
+        rows
+    }
+
+    /// Run a command entered in the command palette
+    /// Commands that cannot be applied leave a message in the status line
+    pub fn run_command(&mut self, command: Command) {
+        match command {
+            Command::Goto(time) => {
+                self.center_on(time.time());
+                self.clamp_time_start();
+            }
+            Command::Add(path) => {
+                let signal = self
+                    .undisplayed_signals()
+                    .find(|(signal_path, _)| path_matches(signal_path, &path));
+                match signal {
+                    Some((_, signal)) => {
+                        self.displayed_items
+                            .push(DisplayItem::Signal(DisplayedSignal::new(Rc::clone(signal))));
+                        self.focus = self.displayed_items.len() - 1;
+                    }
+                    None => {
+                        self.status_message = Some(format!("No signal to add at {}", path));
+                    }
+                }
+            }
+            Command::AddMatching(pattern) => {
+                // Checked when parsing the command
+                let regex = Regex::new(&pattern).unwrap();
+                let signals: Vec<Rc<RefCell<Signal>>> = self
+                    .undisplayed_signals()
+                    .filter(|(path, _)| regex.is_match(path))
+                    .map(|(_, signal)| Rc::clone(signal))
+                    .collect();
+                self.status_message = Some(format!("Added {} signals", signals.len()));
+                if !signals.is_empty() {
+                    self.displayed_items.extend(
+                        signals
+                            .into_iter()
+                            .map(|signal| DisplayItem::Signal(DisplayedSignal::new(signal))),
+                    );
+                    self.focus = self.displayed_items.len() - 1;
+                }
+            }
+            Command::Radix(mode) => match self.displayed_items.get_mut(self.focus) {
+                Some(DisplayItem::Signal(displayed)) if displayed.is_vector() => {
+                    displayed.mode = mode;
+                }
+                _ => {
+                    self.status_message = Some("The focused row is not a vector".to_string());
+                }
+            },
+            Command::Remove => {
+                if self.focus < self.displayed_items.len() {
+                    self.displayed_items.remove(self.focus);
+                    self.focus = min(self.focus, max(1, self.displayed_items.len()) - 1);
+                }
+            }
+            Command::Step(time) => {
+                self.time_step = time;
+            }
+            Command::Map(map) => self.set_value_map(map),
+            Command::Find(pattern) => {
+                self.search = pattern;
+                self.jump_to_match(true);
+            }
+            Command::Unknowns(all) => {
+                let signals: Vec<Rc<RefCell<Signal>>> = match all {
+                    true => self.signals.iter().map(|(_, x)| Rc::clone(x)).collect(),
+                    false => self
+                        .displayed_signals()
+                        .map(|x| Rc::clone(&x.signal))
+                        .collect(),
+                };
+                self.unknowns = signals
+                    .iter()
+                    .flat_map(|signal| {
+                        let times = signal.borrow().unknown_times();
+                        times.into_iter().map(|time| (time, Rc::clone(signal)))
+                    })
+                    .collect();
+                self.unknowns.sort_by_key(|(time, _)| *time);
+                match self.unknowns.is_empty() {
+                    true => self.status_message = Some("No x or z value".to_string()),
+                    false => {
+                        self.mode = AppMode::Unknowns;
+                        self.choice_index = 0;
+                    }
+                }
+            }
+            Command::MapFile(path) => {
+                match fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|content| parse_value_map(&content))
+                {
+                    Ok(map) => self.set_value_map(Some(map)),
+                    Err(e) => self.status_message = Some(format!("Failed to read {}: {}", path, e)),
+                }
+            }
+        }
+    }
+
+    /// Center the view on the next (or previous) match of the searched value in the focused
+    /// signal, searching from the cursor column
+    fn jump_to_match(&mut self, forward: bool) {
+        let (Some(pattern), Some(signal)) = (&self.search, self.focused_signal()) else {
+            return;
+        };
+        let signal = signal.borrow();
+        let found = match forward {
+            true => signal.next_match_after(self.column_range(self.cursor).1 - 1, pattern),
+            false => signal.prev_match_before(self.cursor_time(), pattern),
+        };
+        match found {
+            Some(time) => self.center_on(time),
+            None => {
+                self.status_message = Some(format!(
+                    "No {} match of {}",
+                    if forward { "next" } else { "previous" },
+                    pattern
+                ))
+            }
+        }
+    }
+
+    /// Set the value map of the focused vector
+    fn set_value_map(&mut self, map: Option<HashMap<u64, String>>) {
+        match self.displayed_items.get_mut(self.focus) {
+            Some(DisplayItem::Signal(displayed)) if displayed.is_vector() => {
+                displayed.value_map = map;
+            }
+            _ => {
+                self.status_message = Some("The focused row is not a vector".to_string());
+            }
+        }
+    }
+
+    /// Path of a signal as listed in `signals`
+    fn signal_path(&self, signal: &Rc<RefCell<Signal>>) -> Option<&String> {
+        self.signal_index
+            .paths
+            .get(&Rc::as_ptr(signal))
+            .map(|(path, _)| path)
+    }
+
+    /// Collapse or expand a module in the add-signal picker, and choose its header
+    fn toggle_collapsed(&mut self, path: String) {
+        if !self.collapsed.remove(&path) {
+            self.collapsed.insert(path.clone());
+        }
+        self.choice_index = self
+            .picker_rows()
+            .iter()
+            .position(|row| matches!(row, PickerRow::Module(x, _, _) if *x == path))
+            .unwrap_or(0);
+    }
+
+    /// The signal of the same path in another loaded file, the first of them if there are
+    /// several other files
+    fn counterpart(&self, signal: &Rc<RefCell<Signal>>) -> Option<Rc<RefCell<Signal>>> {
+        let (_, untagged) = self.signal_index.paths.get(&Rc::as_ptr(signal))?;
+        self.signal_index.untagged[untagged]
+            .iter()
+            .find(|x| !Rc::ptr_eq(x, signal))
+            .map(Rc::clone)
+    }
+
+    /// The counterpart of the signal compared with it, when the comparison is on
+    /// A comparison row below the waveform marks the columns where they differ
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::{app::App, command::Command, time::Time};
+    /// use ratatui::{Terminal, backend::TestBackend};
+    /// use std::str::FromStr;
+    ///
+    /// // The clock of `test_diff.vcd` stays high from 30ns to 50ns
+    /// let files = ["assets/verilog/test.vcd", "assets/verilog/test_diff.vcd"];
+    /// let mut app = App::new(files.map(String::from).into()).unwrap();
+    /// let press = |app: &mut App, code| app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    /// press(&mut app, KeyCode::Esc).unwrap();
+    /// app.run_command(Command::Add("test:test_tb:clk".to_string()));
+    /// app.run_command(Command::Step(Time::from_str("2ns").unwrap()));
+    /// let mut terminal = Terminal::new(TestBackend::new(140, 16)).unwrap();
+    /// let mut screen = |app: &mut App| {
+    ///     terminal.draw(|frame| app.draw(frame)).unwrap();
+    ///     let buffer = terminal.backend().buffer();
+    ///     buffer.content().iter().map(|x| x.symbol()).collect::<String>()
+    /// };
+    /// assert!(!screen(&mut app).contains("≠"));
+    ///
+    /// press(&mut app, KeyCode::Char('D')).unwrap();
+    /// let screen = screen(&mut app);
+    /// // The 5 columns from 40ns to 50ns, at 2ns per column
+    /// assert_eq!(screen.matches("≠").count(), 5);
+    /// assert!(screen.contains("≠≠≠≠≠"));
+    /// assert!(screen.contains("First difference: 40ns"));
+    /// ```
+    fn compared(&self, signal: &Rc<RefCell<Signal>>) -> Option<Rc<RefCell<Signal>>> {
+        self.counterpart(signal).filter(|_| self.diff)
+    }
+
+    /// The displayed signals, skipping the dividers
+    fn displayed_signals(&self) -> impl Iterator<Item = &DisplayedSignal> {
+        self.displayed_items.iter().filter_map(|item| match item {
+            DisplayItem::Signal(displayed) => Some(displayed),
+            DisplayItem::Divider(_) => None,
+        })
+    }
+
+    fn focused_signal(&self) -> Option<Rc<RefCell<Signal>>> {
+        match self.displayed_items.get(self.focus) {
+            Some(DisplayItem::Signal(displayed)) => Some(Rc::clone(&displayed.signal)),
+            _ => None,
+        }
+    }
+
+    /// Change the step with `zoom`, keeping the time under the cursor in the cursor column
+    /// The cursor moves left when the window cannot start early enough
+    fn zoom_at_cursor(&mut self, zoom: fn(&mut Time)) {
+        let time = self.cursor_time();
+        zoom(&mut self.time_step);
+        let offset = (self.cursor as u64).saturating_mul(self.time_step.time());
+        self.time_start = Time::new(time.saturating_sub(offset), TimescaleUnit::FS);
+        self.cursor = ((time - self.time_start.time()) / self.time_step.time()) as usize;
+    }
+
+    /// Scroll the view by a quarter of the window, to the right when `forward`
+    fn pan(&mut self, forward: bool) {
+        let offset = max(1, self.arr_size as u64 / 4).saturating_mul(self.time_step.time());
+        match forward {
+            true => {
+                self.time_start.increase(offset);
+                self.clamp_time_start();
+            }
+            false => self.time_start.decrease(offset),
+        }
+    }
+
+    /// First and last columns of the range selected by dragging on a waveform, if any
+    fn selected_columns(&self) -> Option<(usize, usize)> {
+        let start = self.selection?;
+        Some((min(start, self.cursor), max(start, self.cursor)))
+    }
+
+    /// Make sure the visible window does not start after the last event
+    fn clamp_time_start(&mut self) {
+        if self.time_start.time() > self.max_time {
+            self.time_start = Time::new(self.max_time, TimescaleUnit::FS);
+        }
+    }
+
+    /// Move the view so that `time` is in the middle, and put the cursor on it
+    fn center_on(&mut self, time: u64) {
+        let half_window = (self.arr_size as u64 / 2).saturating_mul(self.time_step.time());
+        self.time_start = Time::new(time.saturating_sub(half_window), TimescaleUnit::FS);
+        self.cursor = ((time - self.time_start.time()) / self.time_step.time()) as usize;
+    }
+
+    /// Status line showing the zoom level, the visible window, the cursor, the two markers and
+    /// the time between them
+    fn status_line(&self) -> String {
+        let fmt_marker = |marker: Option<u64>| match marker {
+            Some(t) => Time::new(t, TimescaleUnit::FS).to_string(),
+            None => "-".to_string(),
+        };
+
+        let mut status = format!(
+            "Step: {} | Window: [{}, {}] | End: {} | Cursor: {} | A: {} | B: {}",
+            self.time_step,
+            self.time_start,
+            self.time_start.clone() + (self.arr_size as u64).saturating_mul(self.time_step.time()),
+            Time::new(self.max_time, TimescaleUnit::FS),
+            Time::new(self.cursor_time(), TimescaleUnit::FS),
+            fmt_marker(self.marker_a),
+            fmt_marker(self.marker_b)
+        );
+        if self.time_origin != 0 {
+            let cursor = self.cursor_time();
+            let sign = if cursor < self.time_origin { "-" } else { "+" };
+            status.push_str(&format!(
+                " | Origin: {}, cursor at {}{}",
+                Time::new(self.time_origin, TimescaleUnit::FS),
+                sign,
+                Time::new(cursor.abs_diff(self.time_origin), TimescaleUnit::FS)
+            ));
+        }
+        if let (Some(a), Some(b)) = (self.marker_a, self.marker_b) {
+            let sign = if b < a { "-" } else { "" };
+            status.push_str(&format!(
+                " | B-A: {}{}",
+                sign,
+                Time::new(a.abs_diff(b), TimescaleUnit::FS)
+            ));
+        }
+        if let Some(signal) = self.focused_signal()
+            && let Some(other) = self.compared(&signal)
+        {
+            status.push_str(
+                &match self.first_difference(&signal.borrow(), &other.borrow()) {
+                    Some(t) => format!(" | First difference: {}", Time::new(t, TimescaleUnit::FS)),
+                    None => " | No difference".to_string(),
+                },
+            );
+        }
+        // The edge of a single-bit signal nearest to the cursor, and the time since the previous one
+        if let Some(signal) = self.focused_signal()
+            && signal.borrow().width == 1
+            && let Some((edge, period)) = signal.borrow().nearest_value_change(self.cursor_time())
+        {
+            status.push_str(&format!(" | Edge: {}", Time::new(edge, TimescaleUnit::FS)));
+            if let Some(period) = period {
+                status.push_str(&format!(" (+{})", Time::new(period, TimescaleUnit::FS)));
+            }
+        }
+        let glitches = self.glitch_count();
+        if glitches > 0 {
+            status.push_str(&format!(" | Glitches: {}", glitches));
+        }
+        if let Some(signal) = self.focused_signal()
+            && let Some(path) = self.signal_path(&signal)
+        {
+            // The full value at the cursor, which may not fit in its columns of the waveform
+            let value = match self.displayed_items.get(self.focus) {
+                Some(DisplayItem::Signal(displayed)) => displayed.value_at(self.cursor_time()),
+                _ => None,
+            };
+            status = match value {
+                Some(value) => format!("{} = {} | {}", path, value, status),
+                None => format!("{} | {}", path, status),
+            };
+        }
+        if let Some(message) = &self.status_message {
+            status = format!("{} | {}", message, status);
+        }
+        status
+    }
+
+    /// Number of columns of the displayed signals with a pulse narrower than a column, see
+    /// `Signal::glitch_between`
+    fn glitch_count(&self) -> usize {
+        self.displayed_signals()
+            .map(|x| {
+                let signal = x.signal.borrow();
+                self.display_events(&signal)
+                    .iter()
+                    .enumerate()
+                    .filter(|(column, event)| {
+                        let (start, end) = self.column_range(*column);
+                        matches!(
+                            event,
+                            DisplayEvent::Value(ValueDisplayEvent::MultipleEvent(_))
+                        ) && signal.glitch_between(start, end).is_some()
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Difference of the values of a vector signal sampled at marker B and marker A
+    /// Return None if a marker is not set or any of the two values contains `x` or `z`
+    fn marker_value_delta(&self, signal: &Signal) -> Option<String> {
+        let sample = |time: u64| match signal.value_at(time) {
+            Some(ValueType::Vector(vector)) => vector_to_base_10(&vector),
+            _ => None,
+        };
+        let a = sample(self.marker_a?)? as i128;
+        let b = sample(self.marker_b?)? as i128;
+        let delta = b - a;
+        let sign = if delta < 0 { "-" } else { "" };
+        Some(format!(
+            "B-A: {}{} ({}0x{:x})",
+            sign,
+            delta.abs(),
+            sign,
+            delta.abs()
+        ))
+    }
+
+    /// Period of the signal if it is a regular clock, computed once for its events,
+    /// see `Signal::detect_clock_period`
+    fn clock_period(&self, signal: &Signal) -> Option<u64> {
+        let key = signal as *const Signal as usize;
+        let mut cache = self.clock_periods.borrow_mut();
+        match cache.get(&key) {
+            Some((count, period)) if *count == signal.events.len() => *period,
+            _ => {
+                let period = signal.detect_clock_period();
+                cache.insert(key, (signal.events.len(), period));
+                period
+            }
+        }
+    }
+
+    /// First difference between a signal and its counterpart, computed once for their events,
+    /// see `Signal::first_difference`
+    fn first_difference(&self, signal: &Signal, other: &Signal) -> Option<u64> {
+        let key = (
+            signal as *const Signal as usize,
+            other as *const Signal as usize,
+        );
+        let counts = (signal.events.len(), other.events.len());
+        let mut cache = self.first_differences.borrow_mut();
+        match cache.get(&key) {
+            Some((a, b, first)) if (*a, *b) == counts => *first,
+            _ => {
+                let first = signal.first_difference(other);
+                cache.insert(key, (counts.0, counts.1, first));
+                first
+            }
+        }
+    }
+
+    /// Display events of the signal over the visible window, see `Signal::events_arr_in_range`
+    fn display_events(&self, signal: &Signal) -> Rc<Vec<DisplayEvent>> {
+        let view = (self.time_start.time(), self.time_step.time(), self.arr_size);
+        let mut cache = self.event_cache.borrow_mut();
+        if cache.view != view {
+            cache.view = view;
+            cache.events.clear();
+        }
+        let key = signal as *const Signal as usize;
+        match cache.events.get(&key) {
+            Some((count, events)) if *count == signal.events.len() => Rc::clone(events),
+            _ => {
+                let events = Rc::new(signal.events_arr_in_range(view.0, view.1, view.2));
+                cache
+                    .events
+                    .insert(key, (signal.events.len(), Rc::clone(&events)));
+                events
+            }
+        }
+    }
+
     fn get_value_string_from_a_signal(&self, signal: &Signal) -> String {
-        signal
-            .events_arr_in_range(self.time_start.time(), self.time_step.time(), self.arr_size)
+        self.display_events(signal)
             .iter()
             .map(|x| match x {
+                // The value a column with several transitions settles to
                 DisplayEvent::Value(value_display_event) => match value_display_event {
-                    ValueDisplayEvent::ChangeEvent(value) => value.to_string(),
-                    ValueDisplayEvent::Stay(value) => value.to_string(),
-                    _ => "T".to_string(),
+                    ValueDisplayEvent::ChangeEvent(value)
+                    | ValueDisplayEvent::MultipleEvent(value)
+                    | ValueDisplayEvent::Stay(value) => value.to_string(),
                 },
                 DisplayEvent::Vector(vector_display_event) => match vector_display_event {
-                    VectorDisplayEvent::ChangeEvent(value) => value.to_string(),
-                    VectorDisplayEvent::Stay(value) => value.to_string(),
-                    _ => "T".to_string(),
+                    VectorDisplayEvent::ChangeEvent(value)
+                    | VectorDisplayEvent::MultipleEvent(value)
+                    | VectorDisplayEvent::Stay(value) => value.to_string(),
                 },
             })
             .collect::<String>()
     }
 
-    fn get_lines_from_a_signal(&self, signal: &Signal) -> Vec<Line> {
-        let display_event_arr = signal.events_arr_in_range(
-            self.time_start.time(),
-            self.time_step.time(),
-            self.arr_size,
-        );
+    fn get_lines_from_a_signal(&self, signal: &Signal, displayed: &DisplayedSignal) -> Vec<Line> {
+        let mode = displayed.mode;
+        let display_event_arr = self.display_events(signal);
 
-        let color_green = (*catppuccin::PALETTE
-            .mocha
-            .get_color(catppuccin::ColorName::Green))
-        .into();
-        let color_red = (*catppuccin::PALETTE
-            .mocha
-            .get_color(catppuccin::ColorName::Red))
-        .into();
-
-        let mut lines = display_event_arr.iter().fold(vec![], |mut lines, event| {
-            if lines.len() == 0 {
-                lines = match event {
-                    DisplayEvent::Value(_) => vec![vec![]; 2],
-                    DisplayEvent::Vector(_) => vec![vec![]; 3],
-                };
-            }
+        let theme = &self.theme;
+        let glyphs = self.glyphs;
+
+        // Shade of a column with several transitions, after their number
+        let multiple = |column: usize| {
+            let (start, end) = self.column_range(column);
+            let count = signal.events_between(start, end).count();
+            glyphs.multiple[density_index(count)]
+        };
+
+        let mut lines =
+            display_event_arr
+                .iter()
+                .enumerate()
+                .fold(vec![], |mut lines, (column, event)| {
+                    if lines.len() == 0 {
+                        lines = match event {
+                            DisplayEvent::Value(_) => vec![vec![]; 2],
+                            DisplayEvent::Vector(_) => vec![vec![]; 3],
+                        };
+                    }
 
-            match event {
-                DisplayEvent::Value(value_display_event) => {
-                    let (symbols, color) = match value_display_event {
-                        ValueDisplayEvent::ChangeEvent(value) => {
-                            let symbols = match value {
-                                Value::V0 => S_FALLING_EDGE,
-                                Value::V1 => S_RISING_EDGE,
-                                Value::X => S_STAY_X,
-                                Value::Z => S_STAY_Z,
+                    match event {
+                        DisplayEvent::Value(value_display_event) => {
+                            let (symbols, color) = match value_display_event {
+                                ValueDisplayEvent::ChangeEvent(value) => {
+                                    let symbols = match value {
+                                        Value::V0 => glyphs.falling_edge,
+                                        Value::V1 => glyphs.rising_edge,
+                                        Value::X => glyphs.stay_x,
+                                        Value::Z => glyphs.stay_z,
+                                    };
+                                    (symbols, theme.edge)
+                                }
+                                ValueDisplayEvent::Stay(value) => {
+                                    let symbols = match value {
+                                        Value::V0 => glyphs.stay_0,
+                                        Value::V1 => glyphs.stay_1,
+                                        Value::X => glyphs.stay_x,
+                                        Value::Z => glyphs.stay_z,
+                                    };
+                                    (symbols, theme.stay)
+                                }
+                                ValueDisplayEvent::MultipleEvent(_) => {
+                                    let (start, end) = self.column_range(column);
+                                    match signal.glitch_between(start, end) {
+                                        Some(Value::V0) => (glyphs.glitch_0, theme.edge),
+                                        Some(_) => (glyphs.glitch_1, theme.edge),
+                                        None => ([multiple(column); 2], theme.edge),
+                                    }
+                                }
                             };
-                            (symbols, color_green)
-                        }
-                        ValueDisplayEvent::Stay(value) => {
-                            let symbols = match value {
-                                Value::V0 => S_STAY_0,
-                                Value::V1 => S_STAY_1,
-                                Value::X => S_STAY_X,
-                                Value::Z => S_STAY_Z,
+                            let color = match event.contains_x_or_z() {
+                                true => theme.error,
+                                false => color,
+                            };
+                            let style = match value_display_event {
+                                ValueDisplayEvent::Stay(_) => Style::default().fg(color),
+                                _ => Style::default().fg(color).add_modifier(Modifier::BOLD),
                             };
-                            (symbols, color_green)
+                            lines.iter_mut().enumerate().for_each(|(i, x)| {
+                                x.push(Span::styled(symbols[i], style));
+                            });
                         }
-                        ValueDisplayEvent::MultipleEvent => (S_MULTIPLE, color_green),
-                    };
-                    lines.iter_mut().enumerate().for_each(|(i, x)| {
-                        x.push(Span::styled(symbols[i], Style::default().fg(color)));
-                    });
-                }
-                DisplayEvent::Vector(vector_display_event) => {
-                    let (symbols, color) = match vector_display_event {
-                        VectorDisplayEvent::ChangeEvent(_) => (M_CHANGE, color_green),
-                        VectorDisplayEvent::Stay(vector) => {
-                            let color = match vector_contain_x_or_z(vector) {
-                                true => color_red,
-                                false => color_green,
+                        DisplayEvent::Vector(vector_display_event) => {
+                            let (symbols, color) = match vector_display_event {
+                                VectorDisplayEvent::ChangeEvent(_) => {
+                                    (glyphs.change, theme.changed)
+                                }
+                                VectorDisplayEvent::Stay(_) => {
+                                    match display_event_arr.get(column + 1) {
+                                        Some(DisplayEvent::Vector(
+                                            VectorDisplayEvent::ChangeEvent(_),
+                                        )) => (glyphs.close, theme.changed),
+                                        _ => (glyphs.stay, theme.stay),
+                                    }
+                                }
+                                VectorDisplayEvent::MultipleEvent(_) => {
+                                    ([multiple(column); 3], theme.changed)
+                                }
+                            };
+                            let color = match event.contains_x_or_z() {
+                                true => theme.error,
+                                false => color,
+                            };
+                            // Transitions stand out from the holds
+                            let style = match symbols == glyphs.stay {
+                                true => Style::default().fg(color),
+                                false => Style::default().fg(color).add_modifier(Modifier::BOLD),
                             };
-                            (M_STAY, color)
+                            lines.iter_mut().enumerate().for_each(|(i, x)| {
+                                x.push(Span::styled(symbols[i], style));
+                            });
                         }
-                        VectorDisplayEvent::MultipleEvent => (M_MULTIPLE, color_green),
                     };
-                    lines.iter_mut().enumerate().for_each(|(i, x)| {
-                        x.push(Span::styled(symbols[i], Style::default().fg(color)));
-                    });
-                }
-            };
 
-            lines
-        });
+                    lines
+                });
 
-        // Show binary values for Vector signals in the middle line
+        // Show the values of Vector signals in the middle line, in the format of the mode
+        let width = signal.width as usize;
         let mut start_index = None;
         let mut vector_value: Option<Vector> = None;
         display_event_arr
@@ -468,7 +2825,9 @@ impl<'a> App<'a> {
                                     index + 1..i,
                                     middle_str(
                                         i - index - 1,
-                                        vector_value.clone().unwrap().to_string(),
+                                        displayed
+                                            .format_vector(vector_value.as_ref().unwrap(), width),
+                                        glyphs,
                                     )
                                     .into_iter(),
                                 );
@@ -478,7 +2837,7 @@ impl<'a> App<'a> {
                         start_index = Some(i);
                         vector_value = Some(vector.clone());
                     }
-                    VectorDisplayEvent::MultipleEvent => {}
+                    VectorDisplayEvent::MultipleEvent(_) => {}
                     VectorDisplayEvent::Stay(vector) => match start_index {
                         None => {
                             start_index = Some(i);
@@ -497,15 +2856,215 @@ impl<'a> App<'a> {
                     let len = lines[1].len();
                     lines[1].splice(
                         index + 1..len,
-                        middle_str(len - index - 1, vector_value.unwrap().to_string()).into_iter(),
+                        middle_str(
+                            len - index - 1,
+                            displayed.format_vector(&vector_value.unwrap(), width),
+                            glyphs,
+                        )
+                        .into_iter(),
                     );
                 }
                 _ => {}
             };
         };
 
+        // A taller signal gives the plot more rows, the other columns stay at the bottom
+        if mode == DisplayMode::Analog {
+            let rows = max(lines.len(), displayed.height.saturating_sub(1) as usize);
+            let blank = vec![Span::raw(" "); display_event_arr.len()];
+            while lines.len() < rows {
+                lines.insert(0, blank.clone());
+            }
+            self.draw_analog(&display_event_arr, &mut lines);
+        }
+
         lines.into_iter().map(|x| Line::from(x)).collect::<Vec<_>>()
     }
+
+    /// The single row of a signal in the compact mode, made from the rows of its waveform
+    /// Single-bit signals show their level in the upper or lower half of each cell, vectors show
+    /// the middle row with their values on a colored bar
+    fn compact_line<'b>(&self, signal: &Signal, lines: Vec<Line<'b>>) -> Line<'b> {
+        let theme = &self.theme;
+        let glyphs = self.glyphs;
+        let events = self.display_events(signal);
+        if matches!(events.first(), Some(DisplayEvent::Vector(_))) {
+            let middle = lines.len() / 2;
+            let mut line = lines.into_iter().nth(middle).unwrap_or_default();
+            line.spans
+                .iter_mut()
+                .for_each(|span| span.style = span.style.bg(theme.grid));
+            return line;
+        }
+        let spans: Vec<Span> = events
+            .iter()
+            .map(|event| {
+                let DisplayEvent::Value(event) = event else {
+                    return Span::raw(" ");
+                };
+                let (symbol, value) = match event {
+                    ValueDisplayEvent::Stay(Value::V1) => (glyphs.high, &Value::V1),
+                    ValueDisplayEvent::Stay(Value::V0) => (glyphs.low, &Value::V0),
+                    ValueDisplayEvent::Stay(value)
+                    | ValueDisplayEvent::ChangeEvent(value)
+                    | ValueDisplayEvent::MultipleEvent(value) => (glyphs.changing, value),
+                };
+                let (symbol, color) = match value {
+                    Value::V0 | Value::V1 => (symbol, theme.stay),
+                    Value::X => (glyphs.stay_x[0], theme.error),
+                    Value::Z => (glyphs.stay_z[0], theme.error),
+                };
+                Span::styled(symbol, Style::default().fg(color))
+            })
+            .collect();
+        Line::from(spans)
+    }
+
+    /// Overwrite the columns of a vector signal with a staircase plot of its decimal value
+    /// Columns containing `x` or `z` keep the boxed rendering
+    fn draw_analog(&self, display_event_arr: &[DisplayEvent], lines: &mut [Vec<Span>]) {
+        let glyphs = self.glyphs;
+        let values: Vec<Option<u64>> = display_event_arr
+            .iter()
+            .map(|event| match event {
+                DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(vector))
+                | DisplayEvent::Vector(VectorDisplayEvent::MultipleEvent(vector))
+                | DisplayEvent::Vector(VectorDisplayEvent::Stay(vector)) => {
+                    vector_to_base_10(vector)
+                }
+                DisplayEvent::Value(_) => None,
+            })
+            .collect();
+
+        let height = lines.len();
+        let levels = analog_levels(&values, height * (glyphs.blocks.len() - 1));
+        for (column, level) in levels.into_iter().enumerate() {
+            let Some(level) = level else { continue };
+            lines.iter_mut().enumerate().for_each(|(i, line)| {
+                let base = (height - 1 - i) * (glyphs.blocks.len() - 1);
+                let fill = min(level.saturating_sub(base), glyphs.blocks.len() - 1);
+                line[column] =
+                    Span::styled(glyphs.blocks[fill], Style::default().fg(self.theme.stay));
+            });
+        }
+    }
+}
+
+/// Picker rows of a module and its submodules, see `App::picker_rows`
+/// - `prefix` - prefix of the keys of the modules in `collapsed`
+/// - `indent` - indent level of the header of the module
+/// - `parent` - key of the module the signals of the root module are listed under
+fn collect_picker_rows(
+    module: &Rc<RefCell<Module>>,
+    prefix: &str,
+    indent: usize,
+    parent: Option<&str>,
+    candidates: &HashSet<*const RefCell<Signal>>,
+    collapsed: &HashSet<String>,
+) -> Vec<PickerRow> {
+    let module_ref = module.borrow();
+    // The root module has no header, so its contents are not indented
+    let is_root = module_ref.parent.is_none();
+    let key = match is_root {
+        true => parent.map(|x| x.to_string()),
+        false => Some(format!("{}{}", prefix, Module::get_path_str(module))),
+    };
+    let inner_indent = if is_root { indent } else { indent + 1 };
+
+    let mut rows: Vec<PickerRow> = module_ref
+        .signals
+        .iter()
+        .filter(|signal| candidates.contains(&Rc::as_ptr(signal)))
+        .map(|signal| PickerRow::Signal(Rc::clone(signal), inner_indent, key.clone()))
+        .collect();
+    module_ref.submodules.iter().for_each(|x| {
+        rows.extend(collect_picker_rows(
+            x,
+            prefix,
+            inner_indent,
+            parent,
+            candidates,
+            collapsed,
+        ))
+    });
+
+    if is_root || rows.is_empty() {
+        return rows;
+    }
+    let key = key.unwrap_or_default();
+    // Scopes other than modules, e.g. functions, show their type
+    let name = match module_ref.scope_type() {
+        ScopeType::Module => module_ref.name.clone(),
+        scope_type => format!("{} ({})", module_ref.name, scope_type),
+    };
+    let header = PickerRow::Module(key.clone(), name, indent);
+    if collapsed.contains(&key) {
+        vec![header]
+    } else {
+        std::iter::once(header).chain(rows).collect()
+    }
+}
+
+/// Tags of the waveform files, i.e. the file names without extension
+/// The full paths are used instead if two files have the same name
+fn file_tags(file_paths: &[String]) -> Vec<String> {
+    let stems: Vec<String> = file_paths
+        .iter()
+        .map(|path| {
+            Path::new(path)
+                .file_stem()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or(path.clone())
+        })
+        .collect();
+    let unique = stems.iter().collect::<HashSet<_>>().len() == stems.len();
+    match unique {
+        true => stems,
+        false => file_paths.to_vec(),
+    }
+}
+
+/// Draw the loading screen shown while parsing a waveform file, with the borders of `glyphs`
+pub fn draw_loading(
+    frame: &mut ratatui::Frame<'_>,
+    glyphs: &Glyphs,
+    file_path: &str,
+    read: u64,
+    total: u64,
+) {
+    let vertical = Layout::vertical([Constraint::Max(3)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Max(80)]).flex(Flex::Center);
+    let [area] = vertical.areas(frame.area());
+    let [area] = horizontal.areas(area);
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .border_set(glyphs.border)
+                .borders(Borders::ALL)
+                .title(format!("Loading {}", file_path)),
+        )
+        .ratio(min(read, total) as f64 / max(1, total) as f64);
+    frame.render_widget(gauge, area);
+}
+
+/// Draw a grid line in the blank cells of the given columns, leaving the waveform glyphs as is
+fn draw_grid(line: &mut Line, columns: &[usize], glyph: &'static str, color: Color) {
+    for column in columns {
+        if let Some(span) = line.spans.get_mut(*column)
+            && span.content == " "
+        {
+            span.content = glyph.into();
+            span.style = span.style.fg(color);
+        }
+    }
+}
+
+/// Highlight the span at column `col` of a waveform line
+fn highlight_column(line: &mut Line, col: usize) {
+    if let Some(span) = line.spans.get_mut(col) {
+        span.style = span.style.add_modifier(Modifier::REVERSED);
+    }
 }
 
 impl<'a> App<'a> {
@@ -2,7 +2,19 @@ pub mod app;
 pub mod modules;
 pub mod utils;
 
+pub use modules::check;
+pub use modules::command;
+pub use modules::config;
+pub use modules::display;
+pub use modules::error;
+pub use modules::export;
+pub use modules::history;
+pub use modules::keymap;
 pub use modules::module;
+pub use modules::session;
 pub use modules::signal;
+pub use modules::theme;
 pub use modules::time;
 pub use modules::ui;
+pub use modules::waveform;
+pub use modules::waveform::{LoadOptions, Waveform, load, load_scope, load_with_progress};
@@ -1,12 +1,117 @@
 use cli_log::*;
-use rata_wave::app::App;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
+use rata_wave::{
+    LoadOptions,
+    app::{App, DEFAULT_FILE_PATH, draw_loading},
+    check::Check,
+    config::Config,
+};
 
-use std::io;
+use std::{
+    io::{self, stdout},
+    process::exit,
+};
 
 fn main() -> io::Result<()> {
     init_cli_log!();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("check") {
+        check(&args[1..]);
+    }
+
+    // Waveform files to open, e.g. `rata_wave golden.vcd dut.vcd`, `-` reads the standard input
+    // `--windowed` only keeps the events around the visible window in memory, except for `-`
+    // `--watch` reloads the files when they change, with the `watch` feature
+    // `--scope top.cpu.core0` only loads the signals of a module and its submodules
+    // `--ascii` draws the waveforms with ASCII characters only
+    let mut options = LoadOptions::default();
+    let mut watch = false;
+    let mut ascii = false;
+    let mut file_paths = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--windowed" => options.windowed = true,
+            "--watch" => watch = true,
+            "--ascii" => ascii = true,
+            "--scope" => match args.next() {
+                Some(scope) => options.scope = Some(scope),
+                None => {
+                    eprintln!("Missing the module path of --scope");
+                    exit(1);
+                }
+            },
+            _ => file_paths.push(arg),
+        }
+    }
+    if file_paths.is_empty() {
+        file_paths.push(DEFAULT_FILE_PATH.to_string());
+    }
+
+    if watch && !cfg!(feature = "watch") {
+        eprintln!("--watch requires the `watch` feature");
+        exit(1);
+    }
+
+    // Key bindings and settings of `~/.config/ratawave/config.toml`, see `Config::from_toml`
+    let mut config = Config::load().unwrap_or_else(|e| {
+        eprintln!("Invalid config: {}", e);
+        exit(1);
+    });
+    config.ascii |= ascii;
+    let glyphs = config.glyphs();
+
     let mut terminal = ratatui::init();
-    let app_result = App::default()?.run(&mut terminal);
+    execute!(stdout(), EnableMouseCapture)?;
+    // Show the progress of parsing, which can take a while for large files
+    let app = App::with_progress(file_paths, &options, &mut |file_path, read, total| {
+        let _ = terminal.draw(|frame| draw_loading(frame, glyphs, file_path, read, total));
+    });
+    let app_result = app.map(|mut app| {
+        app.set_config(config);
+        #[cfg(feature = "watch")]
+        if watch {
+            app.watch_files();
+        }
+        app.run(&mut terminal)
+    });
+    execute!(stdout(), DisableMouseCapture)?;
     ratatui::restore();
-    app_result
+    match app_result {
+        Ok(run_result) => run_result,
+        Err(e) => {
+            eprintln!("Failed to load the waveform: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Check the value of a signal without the UI, e.g.
+/// `rata_wave check file.vcd --signal top.clk --at 100ns --eq 1 [--radix hex]`
+/// Exit with 0 if the value is the expected one, 1 if it is not and 2 if the check cannot be run
+fn check(args: &[String]) -> ! {
+    let check = Check::from_args(args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!(
+            "Usage: rata_wave check <file> --signal <path> --at <time> --eq <value> [--radix <bin|dec|sdec|hex>]"
+        );
+        exit(2);
+    });
+    let waveform = rata_wave::load(&check.file_path).unwrap_or_else(|e| {
+        eprintln!("Failed to load the waveform: {}", e);
+        exit(2);
+    });
+    match check.run(&waveform) {
+        Ok(value) => {
+            println!("{} is {} at {}", check.signal, value, check.at);
+            exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(e.exit_code());
+        }
+    }
 }
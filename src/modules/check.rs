@@ -0,0 +1,176 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{display::DisplayMode, signal::ValueType, time::Time, waveform::Waveform};
+
+/// Assertion of the value of a signal at a time, checked without the UI, e.g.
+/// `rata_wave check file.vcd --signal top.clk --at 100ns --eq 1`
+/// - `signal` - path of the signal, as shown in the UI (see `Waveform::signal_by_path`) or with
+///   the modules and the signal separated by dots
+/// - `at` - time of the sample
+/// - `eq` - expected value, the value of a vector is compared in the given radix
+/// - `radix` - `bin` (default), `dec`, `sdec` or `hex`, see `DisplayMode::name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    pub file_path: String,
+    pub signal: String,
+    pub at: Time,
+    pub eq: String,
+    pub radix: DisplayMode,
+}
+
+/// Reasons a check fails
+/// - `Mismatch` - the signal has another value than the expected one
+/// - `Unresolved` - the check cannot be run, e.g. the signal is not in the waveform
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckError {
+    Mismatch(String),
+    Unresolved(String),
+}
+
+impl CheckError {
+    /// Exit code of `rata_wave check`, 1 for a mismatch and 2 for a check that cannot be run
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CheckError::Mismatch(_) => 1,
+            CheckError::Unresolved(_) => 2,
+        }
+    }
+}
+
+impl Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::Mismatch(message) | CheckError::Unresolved(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl Check {
+    /// Parse the arguments following `check`
+    ///
+    /// ```
+    /// use rata_wave::check::Check;
+    /// use rata_wave::display::DisplayMode;
+    ///
+    /// let args = "file.vcd --signal top.bus --at 10ns --eq ff --radix hex";
+    /// let args: Vec<String> = args.split(' ').map(String::from).collect();
+    /// let check = Check::from_args(&args).unwrap();
+    /// assert_eq!(check.file_path, "file.vcd");
+    /// assert_eq!(check.at.time(), 10_000_000);
+    /// assert_eq!(check.radix, DisplayMode::Hex);
+    ///
+    /// let args: Vec<String> = ["file.vcd", "--signal", "top.clk"].map(String::from).into();
+    /// assert_eq!(Check::from_args(&args).unwrap_err(), "Missing --at");
+    /// ```
+    pub fn from_args(args: &[String]) -> Result<Check, String> {
+        let (mut file_path, mut signal, mut at, mut eq) = (None, None, None, None);
+        let mut radix = DisplayMode::Binary;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing the value of {}", arg))
+            };
+            match arg.as_str() {
+                "--signal" => signal = Some(value()?.clone()),
+                "--at" => {
+                    let time = value()?;
+                    at = Some(
+                        Time::from_str(time)
+                            .map_err(|e| format!("Invalid time {}: {}", time, e.message()))?,
+                    );
+                }
+                "--eq" => eq = Some(value()?.clone()),
+                "--radix" => {
+                    let name = value()?;
+                    radix = DisplayMode::from_name(name)
+                        .filter(|x| *x != DisplayMode::Analog)
+                        .ok_or_else(|| format!("Unknown radix: {}", name))?;
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+                _ if file_path.is_none() => file_path = Some(arg.clone()),
+                _ => return Err(format!("Unexpected argument: {}", arg)),
+            }
+        }
+        Ok(Check {
+            file_path: file_path.ok_or("Missing the waveform file")?,
+            signal: signal.ok_or("Missing --signal")?,
+            at: at.ok_or("Missing --at")?,
+            eq: eq.ok_or("Missing --eq")?,
+            radix,
+        })
+    }
+
+    /// Sample the signal in the waveform and compare it with the expected value
+    /// Return the sampled value, or why the check fails
+    /// Leading zeros are ignored when comparing binary and hex values, as is the case of letters
+    ///
+    /// ```
+    /// use rata_wave::check::{Check, CheckError};
+    /// use rata_wave::display::DisplayMode;
+    ///
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// let mut check = Check {
+    ///     file_path: "assets/verilog/test_1.vcd".to_string(),
+    ///     signal: "test_tb.clk".to_string(),
+    ///     at: "10ns".parse().unwrap(),
+    ///     eq: "1".to_string(),
+    ///     radix: DisplayMode::Binary,
+    /// };
+    /// assert_eq!(check.run(&waveform), Ok("1".to_string()));
+    ///
+    /// check.signal = "test_tb.i1.counter".to_string();
+    /// check.eq = "0".to_string();
+    /// assert_eq!(check.run(&waveform), Ok("0000".to_string()));
+    /// check.eq = "3".to_string();
+    /// let error = check.run(&waveform).unwrap_err();
+    /// assert_eq!(error.to_string(), "test_tb.i1.counter is 0000 at 10ns, expected 3");
+    /// assert_eq!(error.exit_code(), 1);
+    ///
+    /// // A typo in the path cannot be told apart from a regression by a mismatch
+    /// check.signal = "test_tb.data".to_string();
+    /// let error = check.run(&waveform).unwrap_err();
+    /// assert_eq!(error, CheckError::Unresolved("No signal test_tb.data".to_string()));
+    /// assert_eq!(error.exit_code(), 2);
+    /// ```
+    pub fn run(&self, waveform: &Waveform) -> Result<String, CheckError> {
+        let signal = waveform
+            .signal_by_path(&self.signal)
+            .ok_or_else(|| CheckError::Unresolved(format!("No signal {}", self.signal)))?;
+        let signal = signal.borrow();
+
+        let value = match waveform.value_at(&signal, &self.at) {
+            Some(ValueType::Value(value)) => value.to_string(),
+            Some(ValueType::Vector(vector)) => {
+                self.radix.format_vector(&vector, signal.width as usize)
+            }
+            None => {
+                return Err(CheckError::Unresolved(format!(
+                    "{} has no value at {}",
+                    self.signal, self.at
+                )));
+            }
+        };
+
+        let normalize = |s: &str| {
+            let s = s.to_ascii_lowercase();
+            match self.radix {
+                DisplayMode::Binary | DisplayMode::Hex if s.len() > 1 => {
+                    let trimmed = s.trim_start_matches('0');
+                    if trimmed.is_empty() { "0" } else { trimmed }.to_string()
+                }
+                _ => s,
+            }
+        };
+        if normalize(&value) == normalize(&self.eq) {
+            Ok(value)
+        } else {
+            Err(CheckError::Mismatch(format!(
+                "{} is {} at {}, expected {}",
+                self.signal, value, self.at, self.eq
+            )))
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use regex::Regex;
+
+use super::{
+    display::{DisplayMode, parse_value_map},
+    signal::ValuePattern,
+    time::Time,
+};
+
+/// A command entered in the command palette
+/// - `Goto` - center the view on a time, e.g. `goto 100ns`
+/// - `Add` - display a signal by its path, e.g. `add top.clk`
+/// - `AddMatching` - display all signals whose path matches a regex, e.g. `add /cpu\.alu\..*/`
+/// - `Radix` - set the display mode of the focused vector, e.g. `radix hex`
+/// - `Remove` - remove the focused row
+/// - `Step` - set the time of a column, e.g. `step 5ns`, to any positive time and not only to
+///   the 1-2-5 sequence the zoom keys go through
+/// - `Map` - set the value map of the focused vector, e.g. `map 0=IDLE,1=BUSY`, or remove it with
+///   `map`, see `display::parse_value_map`
+/// - `MapFile` - set the value map of the focused vector from a file, e.g. `map states.txt`
+/// - `Find` - search a value in the focused signal, e.g. `find 5` or `find 0b1x`, then `n`/`N`
+///   jump between its matches instead of the edges, `find` alone ends the search, see
+///   `signal::ValuePattern`
+/// - `Unknowns` - list the `x` and `z` values of the displayed signals with `xz`, or of all
+///   signals with `xz all`, see `Signal::unknown_times`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Goto(Time),
+    Add(String),
+    AddMatching(String),
+    Radix(DisplayMode),
+    Remove,
+    Step(Time),
+    Map(Option<HashMap<u64, String>>),
+    MapFile(String),
+    Find(Option<ValuePattern>),
+    Unknowns(bool),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseCommandError {
+    message: String,
+}
+
+impl ParseCommandError {
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl Display for ParseCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Parse command error: {}", self.message)
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseCommandError;
+
+    /// Parse a command, an optional leading `:` is ignored
+    ///
+    /// ```
+    /// use rata_wave::command::Command;
+    /// use rata_wave::display::DisplayMode;
+    /// use rata_wave::signal::ValuePattern;
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// assert_eq!(":goto 100ns".parse(), Ok(Command::Goto(Time::new(100, TimescaleUnit::NS))));
+    /// assert_eq!("add top.clk".parse(), Ok(Command::Add("top.clk".to_string())));
+    /// assert_eq!("add /top.*clk/".parse(), Ok(Command::AddMatching("top.*clk".to_string())));
+    /// assert!("add /top(/".parse::<Command>().is_err());
+    /// assert_eq!("radix hex".parse(), Ok(Command::Radix(DisplayMode::Hex)));
+    /// assert_eq!("remove".parse(), Ok(Command::Remove));
+    /// assert_eq!("step 5ns".parse(), Ok(Command::Step(Time::new(5, TimescaleUnit::NS))));
+    ///
+    /// let error = "zoom 2".parse::<Command>().unwrap_err();
+    /// assert_eq!(error.message(), "Unknown command: zoom");
+    /// assert!("step 0ns".parse::<Command>().is_err());
+    ///
+    /// // Steps out of the 1-2-5 sequence and fractional mantissas
+    /// assert_eq!("step 25ns".parse(), Ok(Command::Step(Time::new(25, TimescaleUnit::NS))));
+    /// assert_eq!("step 2.5ns".parse(), Ok(Command::Step(Time::new(2500, TimescaleUnit::PS))));
+    /// assert!("step 0.5fs".parse::<Command>().is_err());
+    /// assert!("radix oct".parse::<Command>().is_err());
+    ///
+    /// let map = [(0, "IDLE".to_string()), (1, "BUSY".to_string())].into();
+    /// assert_eq!("map 0=IDLE, 1=BUSY".parse(), Ok(Command::Map(Some(map))));
+    /// assert_eq!("map".parse(), Ok(Command::Map(None)));
+    /// assert_eq!("map states.txt".parse(), Ok(Command::MapFile("states.txt".to_string())));
+    /// assert!("map 0=IDLE,one=BUSY".parse::<Command>().is_err());
+    ///
+    /// assert_eq!("find 0x10".parse(), Ok(Command::Find(Some(ValuePattern::Number(16)))));
+    /// assert_eq!("find".parse(), Ok(Command::Find(None)));
+    /// assert!("find idle".parse::<Command>().is_err());
+    ///
+    /// assert_eq!("xz".parse(), Ok(Command::Unknowns(false)));
+    /// assert_eq!("xz all".parse(), Ok(Command::Unknowns(true)));
+    /// assert!("xz some".parse::<Command>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |message: String| ParseCommandError { message };
+        let s = s.trim();
+        let s = s.strip_prefix(':').unwrap_or(s).trim_start();
+        let (name, argument) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let argument = argument.trim();
+
+        let parse_time =
+            |argument: &str| Time::from_str(argument).map_err(|e| error(e.message().to_string()));
+        let command = match name {
+            "" => return Err(error("Empty command".to_string())),
+            "goto" => Command::Goto(parse_time(argument)?),
+            "add" if argument.is_empty() => return Err(error("Missing signal path".to_string())),
+            "add" => match argument.strip_prefix('/').and_then(|x| x.strip_suffix('/')) {
+                Some(pattern) => {
+                    Regex::new(pattern)
+                        .map_err(|_| error(format!("Invalid regex: {}", pattern)))?;
+                    Command::AddMatching(pattern.to_string())
+                }
+                None => Command::Add(argument.to_string()),
+            },
+            "radix" => Command::Radix(
+                DisplayMode::from_name(argument)
+                    .ok_or_else(|| error(format!("Unknown radix: {}", argument)))?,
+            ),
+            "remove" => Command::Remove,
+            "map" if argument.is_empty() => Command::Map(None),
+            "map" if argument.contains('=') => {
+                Command::Map(Some(parse_value_map(argument).map_err(error)?))
+            }
+            "map" => Command::MapFile(argument.to_string()),
+            "find" if argument.is_empty() => Command::Find(None),
+            "find" => Command::Find(Some(argument.parse().map_err(error)?)),
+            "xz" => match argument {
+                "" => Command::Unknowns(false),
+                "all" => Command::Unknowns(true),
+                _ => return Err(error(format!("Unknown argument of xz: {}", argument))),
+            },
+            "step" => {
+                let time = parse_time(argument)?;
+                if time.time() == 0 {
+                    return Err(error("Step must be positive".to_string()));
+                }
+                Command::Step(time)
+            }
+            _ => return Err(error(format!("Unknown command: {}", name))),
+        };
+        if matches!(command, Command::Remove) && !argument.is_empty() {
+            return Err(error("remove takes no argument".to_string()));
+        }
+        Ok(command)
+    }
+}
+
+/// Whether the path of a signal (see `Signal::output_path`) ends with the path entered in a
+/// command, where `.` separates modules and the signal, and the trailing range and `(code)` are
+/// optional
+///
+/// ```
+/// use rata_wave::command::path_matches;
+///
+/// assert!(path_matches("test_tb->i1:clk(\")", "test_tb.i1.clk"));
+/// assert!(path_matches("test_tb->i1:clk(\")", "i1.clk"));
+/// assert!(path_matches("test_tb->i1:clk(\")", "test_tb->i1:clk(\")"));
+/// assert!(!path_matches("test_tb->i1:clk(\")", "test_tb.clk"));
+/// assert!(!path_matches("test_tb->i1:clk(\")", "1.clk"));
+/// assert!(path_matches("test_tb:data[7:0](#)", "test_tb.data"));
+/// ```
+pub fn path_matches(path: &str, query: &str) -> bool {
+    let normalize = |path: &str| {
+        let path = match path.rfind('(') {
+            Some(index) if path.ends_with(')') => &path[..index],
+            _ => path,
+        };
+        let path = match path.rfind('[') {
+            Some(index) if path.ends_with(']') => &path[..index],
+            _ => path,
+        };
+        path.replace("->", ".").replace(':', ".")
+    };
+    let (path, query) = (normalize(path), normalize(query.trim()));
+    path == query || path.ends_with(&format!(".{}", query))
+}
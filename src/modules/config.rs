@@ -0,0 +1,104 @@
+use std::{fs, io};
+
+use serde::Deserialize;
+
+use super::{
+    keymap::KeyMap,
+    ui::{ASCII_GLYPHS, Glyphs, UNICODE_GLYPHS},
+};
+use crate::utils::RULER_TICK_SPACING;
+
+#[derive(Deserialize, Default)]
+struct RulerConfig {
+    tick_spacing: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+struct DisplayConfig {
+    #[serde(default)]
+    ascii: bool,
+}
+
+#[derive(Deserialize)]
+struct RawConfig {
+    #[serde(default)]
+    ruler: RulerConfig,
+    #[serde(default)]
+    display: DisplayConfig,
+}
+
+/// Settings of the config file, see `KeyMap::config_path`
+/// - `keymap` - the keys of the `[keys]` table, see `KeyMap::from_toml`
+/// - `tick_spacing` - the minimum number of columns between two ticks of the time ruler, given
+///   by `tick_spacing` in the `[ruler]` table
+/// - `ascii` - whether the waveforms are drawn with ASCII characters only, given by `ascii` in
+///   the `[display]` table or by the `--ascii` flag, see `ui::ASCII_GLYPHS`
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub keymap: KeyMap,
+    pub tick_spacing: u64,
+    pub ascii: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            keymap: KeyMap::default(),
+            tick_spacing: RULER_TICK_SPACING,
+            ascii: false,
+        }
+    }
+}
+
+impl Config {
+    /// Parse the content of a config file, the missing settings keep their default
+    ///
+    /// ```
+    /// use rata_wave::config::Config;
+    ///
+    /// let config = Config::from_toml("[ruler]\ntick_spacing = 20").unwrap();
+    /// assert_eq!(config.tick_spacing, 20);
+    /// assert_eq!(Config::from_toml("").unwrap().tick_spacing, 10);
+    /// assert!(Config::from_toml("[display]\nascii = true").unwrap().ascii);
+    /// assert!(!Config::from_toml("").unwrap().ascii);
+    ///
+    /// assert!(Config::from_toml("[ruler]\ntick_spacing = 0").is_err());
+    /// assert!(Config::from_toml("[keys]\nzoom_in = \"plus\"").is_err());
+    /// ```
+    pub fn from_toml(content: &str) -> Result<Config, String> {
+        let keymap = KeyMap::from_toml(content)?;
+        let raw: RawConfig = toml::from_str(content).map_err(|e| e.to_string())?;
+        let tick_spacing = match raw.ruler.tick_spacing {
+            Some(0) => return Err("tick_spacing must be at least 1".to_string()),
+            Some(tick_spacing) => tick_spacing,
+            None => RULER_TICK_SPACING,
+        };
+        Ok(Config {
+            keymap,
+            tick_spacing,
+            ascii: raw.display.ascii,
+        })
+    }
+
+    /// Glyphs the waveforms are drawn with, `ASCII_GLYPHS` in the ASCII mode
+    pub fn glyphs(&self) -> &'static Glyphs {
+        match self.ascii {
+            true => &ASCII_GLYPHS,
+            false => &UNICODE_GLYPHS,
+        }
+    }
+
+    /// Load the config file, or the default config if there is no config file
+    pub fn load() -> io::Result<Config> {
+        let Some(path) = KeyMap::config_path().filter(|x| x.exists()) else {
+            return Ok(Config::default());
+        };
+        let content = fs::read_to_string(&path)?;
+        Config::from_toml(&content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+}
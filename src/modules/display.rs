@@ -0,0 +1,432 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+use vcd::{Value, VarType, Vector};
+
+use super::signal::{Signal, ValueType, extend_vector, vector_to_base_10};
+use crate::utils::vector_contain_x_or_z;
+
+/// How the waveform of a displayed signal is drawn
+/// Values are always drawn as edges, the mode only applies to vectors
+/// - `Binary`, `Decimal`, `SignedDecimal`, `Hex` - boxed numbers in the given format
+/// - `Analog` - a staircase plot of the decimal value
+///
+/// Saved to session files by the short names of `DisplayMode::name`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    #[serde(rename = "bin")]
+    Binary,
+    #[serde(rename = "dec")]
+    Decimal,
+    #[serde(rename = "sdec")]
+    SignedDecimal,
+    #[serde(rename = "hex")]
+    Hex,
+    #[serde(rename = "analog")]
+    Analog,
+}
+
+impl DisplayMode {
+    /// Switch between the analog mode and the binary format
+    pub fn toggle(self) -> Self {
+        match self {
+            DisplayMode::Analog => DisplayMode::Binary,
+            _ => DisplayMode::Analog,
+        }
+    }
+
+    /// The next mode in the order binary, decimal, signed decimal, hex and analog
+    pub fn next(self) -> Self {
+        use DisplayMode::*;
+        match self {
+            Binary => Decimal,
+            Decimal => SignedDecimal,
+            SignedDecimal => Hex,
+            Hex => Analog,
+            Analog => Binary,
+        }
+    }
+
+    /// The mode of the given short name, see `DisplayMode::name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        use DisplayMode::*;
+        [Binary, Decimal, SignedDecimal, Hex, Analog]
+            .into_iter()
+            .find(|x| x.name() == name)
+    }
+
+    /// Short name of the mode shown next to the name of the signal
+    pub fn name(self) -> &'static str {
+        use DisplayMode::*;
+        match self {
+            Binary => "bin",
+            Decimal => "dec",
+            SignedDecimal => "sdec",
+            Hex => "hex",
+            Analog => "analog",
+        }
+    }
+
+    /// Format the value of a vector of `width` bits in this mode, the analog mode labels values
+    /// in binary
+    /// The vector is first extended to `width` bits, see `extend_vector`
+    /// A decimal value containing `x` or `z` is shown as `x`, a hex digit is `z` when all its
+    /// bits are `z` and `x` when any of them is `x` or `z`
+    /// A decimal value that does not fit in 64 bits is shown in hex, prefixed by `0x`
+    ///
+    /// ```
+    /// use rata_wave::display::DisplayMode;
+    ///
+    /// let vector = "11110110".parse().unwrap();
+    /// assert_eq!(DisplayMode::Binary.format_vector(&vector, 8), "11110110");
+    /// assert_eq!(DisplayMode::Decimal.format_vector(&vector, 8), "246");
+    /// assert_eq!(DisplayMode::SignedDecimal.format_vector(&vector, 8), "-10");
+    /// assert_eq!(DisplayMode::Hex.format_vector(&vector, 8), "f6");
+    ///
+    /// let vector = "zzzz10x1".parse().unwrap();
+    /// assert_eq!(DisplayMode::Decimal.format_vector(&vector, 8), "x");
+    /// assert_eq!(DisplayMode::Hex.format_vector(&vector, 8), "zx");
+    /// // The missing high bits of the first hex digit are 0
+    /// assert_eq!(DisplayMode::Hex.format_vector(&"11111".parse().unwrap(), 5), "1f");
+    ///
+    /// // A value written with fewer bits than the width is not negative
+    /// let vector = "110".parse().unwrap();
+    /// assert_eq!(DisplayMode::SignedDecimal.format_vector(&vector, 8), "6");
+    /// assert_eq!(DisplayMode::Binary.format_vector(&vector, 8), "00000110");
+    /// assert_eq!(DisplayMode::Hex.format_vector(&vector, 12), "006");
+    ///
+    /// let vector = format!("1{}", "0".repeat(79)).parse().unwrap();
+    /// assert_eq!(DisplayMode::Decimal.format_vector(&vector, 80), "0x80000000000000000000");
+    /// assert_eq!(DisplayMode::SignedDecimal.format_vector(&vector, 80), "0x80000000000000000000");
+    /// assert_eq!(DisplayMode::SignedDecimal.format_vector(&"101".parse().unwrap(), 80), "5");
+    /// ```
+    pub fn format_vector(self, vector: &Vector, width: usize) -> String {
+        let vector = &extend_vector(vector, width);
+        use DisplayMode::*;
+        match self {
+            Binary | Analog => vector.to_string(),
+            Decimal | SignedDecimal if vector_contain_x_or_z(vector) => "x".to_string(),
+            Decimal => match vector_to_base_10(vector) {
+                Some(value) => value.to_string(),
+                None => format!("0x{}", Hex.format_vector(vector, width)),
+            },
+            // A value with its sign bit set only fits in 64 bits if the vector is not wider
+            SignedDecimal => match vector_to_base_10(vector) {
+                Some(value) if vector.iter().next() == Some(Value::V1) => {
+                    (value as i128 - (1i128 << vector.len())).to_string()
+                }
+                Some(value) => value.to_string(),
+                None => format!("0x{}", Hex.format_vector(vector, width)),
+            },
+            Hex => {
+                let padding = (4 - vector.len() % 4) % 4;
+                let bits: Vec<Value> = std::iter::repeat_n(Value::V0, padding)
+                    .chain(vector.iter())
+                    .collect();
+                bits.chunks(4)
+                    .map(|nibble| {
+                        if nibble.iter().all(|x| *x == Value::Z) {
+                            'z'
+                        } else if nibble.iter().any(|x| matches!(x, Value::X | Value::Z)) {
+                            'x'
+                        } else {
+                            let digit = nibble
+                                .iter()
+                                .fold(0, |acc, x| acc * 2 + (*x == Value::V1) as u32);
+                            char::from_digit(digit, 16).unwrap()
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Parse a value map of a vector, e.g. the names of the states of a state machine
+/// Entries are `value=label`, separated by commas or line breaks, where the value is decimal or
+/// prefixed by `0x` or `0b`
+/// Empty lines and lines starting with `#` are ignored
+///
+/// ```
+/// use rata_wave::display::parse_value_map;
+///
+/// let map = parse_value_map("0=IDLE, 1=FETCH,0x2=EXEC").unwrap();
+/// assert_eq!(map[&2], "EXEC");
+///
+/// let map = parse_value_map("# States of the FSM\n0b00 = IDLE\n\n0b11 = DONE\n").unwrap();
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map[&3], "DONE");
+///
+/// assert!(parse_value_map("IDLE").is_err());
+/// assert!(parse_value_map("x=IDLE").is_err());
+/// ```
+pub fn parse_value_map(s: &str) -> Result<HashMap<u64, String>, String> {
+    s.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (value, label) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Expected value=label: {}", entry))?;
+            let value = value.trim();
+            let parsed = match (value.strip_prefix("0x"), value.strip_prefix("0b")) {
+                (Some(hex), _) => u64::from_str_radix(hex, 16),
+                (_, Some(bin)) => u64::from_str_radix(bin, 2),
+                _ => value.parse(),
+            };
+            let value = parsed.map_err(|_| format!("Invalid value: {}", value))?;
+            Ok((value, label.trim().to_string()))
+        })
+        .collect()
+}
+
+/// A signal shown in the waveform area, together with how it is drawn
+/// - `alias` - a name shown instead of the name of the signal
+/// - `value_map` - labels shown instead of the values of a vector, see `parse_value_map`
+/// - `height` - number of rows of the signal, between `MIN_HEIGHT` and `MAX_HEIGHT`
+pub struct DisplayedSignal {
+    pub signal: Rc<RefCell<Signal>>,
+    pub mode: DisplayMode,
+    pub alias: Option<String>,
+    pub value_map: Option<HashMap<u64, String>>,
+    pub height: u16,
+}
+
+/// Rows of a displayed signal, the value line and the two or three lines of its waveform
+pub const DEFAULT_HEIGHT: u16 = 4;
+pub const MIN_HEIGHT: u16 = 3;
+pub const MAX_HEIGHT: u16 = 24;
+
+impl DisplayedSignal {
+    /// Display a signal in the mode guessed by `default_mode`
+    ///
+    /// ```
+    /// use rata_wave::display::{DisplayMode, DisplayedSignal};
+    ///
+    /// let waveform = rata_wave::load("assets/verilog/var_types.vcd").unwrap();
+    /// let mode = |path| DisplayedSignal::new(waveform.signal_by_path(path).unwrap()).mode;
+    /// assert_eq!(mode("top:state"), DisplayMode::Binary);
+    /// assert_eq!(mode("top:count"), DisplayMode::SignedDecimal);
+    /// assert_eq!(mode("top:WIDTH"), DisplayMode::Decimal);
+    ///
+    /// // The value of a wide register is shown in full
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Reg, 80, 0u32.into(), "wide".to_string(), None);
+    /// let mut wide = Signal::from_var(&var);
+    /// wide.add_event(0, ValueType::Vector("1".repeat(80).parse().unwrap()));
+    /// let displayed = DisplayedSignal::new(Rc::new(RefCell::new(wide)));
+    /// assert_eq!(displayed.mode, DisplayMode::Hex);
+    /// assert_eq!(displayed.value_at(0).unwrap(), "f".repeat(20));
+    /// ```
+    pub fn new(signal: Rc<RefCell<Signal>>) -> Self {
+        let mode = default_mode(&signal.borrow());
+        Self {
+            signal,
+            mode,
+            alias: None,
+            value_map: None,
+            height: DEFAULT_HEIGHT,
+        }
+    }
+
+    /// Format the value of a vector of `width` bits, the label of its value in the value map if
+    /// any, or the value in the display mode, see `DisplayMode::format_vector`
+    ///
+    /// ```
+    /// use rata_wave::display::{DisplayedSignal, parse_value_map};
+    /// use rata_wave::signal::Signal;
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Reg, 2, 0u32.into(), "state".to_string(), None);
+    /// let mut state = DisplayedSignal::new(Rc::new(RefCell::new(Signal::from_var(&var))));
+    /// state.value_map = Some(parse_value_map("0=IDLE,1=FETCH").unwrap());
+    ///
+    /// assert_eq!(state.format_vector(&"01".parse().unwrap(), 2), "FETCH");
+    /// // Unmapped values fall back to the display mode
+    /// assert_eq!(state.format_vector(&"11".parse().unwrap(), 2), "11");
+    /// assert_eq!(state.format_vector(&"x1".parse().unwrap(), 2), "x1");
+    /// ```
+    pub fn format_vector(&self, vector: &Vector, width: usize) -> String {
+        let label = self
+            .value_map
+            .as_ref()
+            .zip(vector_to_base_10(vector))
+            .and_then(|(map, value)| map.get(&value));
+        match label {
+            Some(label) => label.clone(),
+            None => self.mode.format_vector(vector, width),
+        }
+    }
+
+    /// Change the height by `delta` rows, within `MIN_HEIGHT` and `MAX_HEIGHT`
+    ///
+    /// ```
+    /// use rata_wave::display::{DEFAULT_HEIGHT, DisplayedSignal, MAX_HEIGHT, MIN_HEIGHT};
+    /// use rata_wave::signal::Signal;
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 8, 0u32.into(), "data".to_string(), None);
+    /// let mut data = DisplayedSignal::new(Rc::new(RefCell::new(Signal::from_var(&var))));
+    /// data.resize(2);
+    /// assert_eq!(data.height, DEFAULT_HEIGHT + 2);
+    /// data.resize(-100);
+    /// assert_eq!(data.height, MIN_HEIGHT);
+    /// data.resize(100);
+    /// assert_eq!(data.height, MAX_HEIGHT);
+    /// ```
+    pub fn resize(&mut self, delta: i32) {
+        self.height =
+            (self.height as i32 + delta).clamp(MIN_HEIGHT as i32, MAX_HEIGHT as i32) as u16;
+    }
+
+    /// Whether the signal is a vector, judging from its first event
+    pub fn is_vector(&self) -> bool {
+        matches!(
+            self.signal.borrow().events.first(),
+            Some((_, ValueType::Vector(_)))
+        )
+    }
+
+    /// Name of the displayed signal, i.e. the alias if any, or `Signal::output_name`
+    ///
+    /// ```
+    /// use rata_wave::display::DisplayedSignal;
+    /// use rata_wave::signal::Signal;
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut displayed = DisplayedSignal::new(Rc::new(RefCell::new(Signal::from_var(&var))));
+    /// assert_eq!(displayed.output_name(), "clk(!)");
+    ///
+    /// displayed.alias = Some("clock".to_string());
+    /// assert_eq!(displayed.output_name(), "clock");
+    /// ```
+    pub fn output_name(&self) -> String {
+        match &self.alias {
+            Some(alias) => alias.clone(),
+            None => self.signal.borrow().output_name(),
+        }
+    }
+
+    /// Value of the signal at `time` (in fs), vectors are formatted in the display mode
+    /// Return None before the first event of the signal
+    ///
+    /// ```
+    /// use rata_wave::display::{DisplayMode, DisplayedSignal};
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 8, 0u32.into(), "bus".to_string(), None);
+    /// let mut bus = Signal::from_var(&var);
+    /// bus.add_event(10, ValueType::Vector("11110110".parse().unwrap()));
+    ///
+    /// let mut displayed = DisplayedSignal::new(Rc::new(RefCell::new(bus)));
+    /// assert_eq!(displayed.value_at(5), None);
+    /// assert_eq!(displayed.value_at(10).unwrap(), "11110110");
+    /// displayed.mode = DisplayMode::Hex;
+    /// assert_eq!(displayed.value_at(20).unwrap(), "f6");
+    /// ```
+    pub fn value_at(&self, time: u64) -> Option<String> {
+        let value = self.signal.borrow().value_at(time)?;
+        Some(self.format_value(&value))
+    }
+
+    /// A value of the signal, a vector in the format of the mode
+    pub fn format_value(&self, value: &ValueType) -> String {
+        match value {
+            ValueType::Value(value) => value.to_string(),
+            ValueType::Vector(vector) => {
+                self.format_vector(vector, self.signal.borrow().width as usize)
+            }
+        }
+    }
+}
+
+/// Vectors wider than this are not shown in binary by default, see `default_mode`
+pub const WIDE_VECTOR: u32 = 8;
+
+/// Initial display mode of a signal, from its type, name and width
+/// - integers are shown in signed decimal, parameters and times in decimal
+/// - addresses and pointers, judging from their names, are shown in hex
+/// - other vectors wider than `WIDE_VECTOR` bits are shown in hex for wires and in decimal for
+///   the others, e.g. the counters kept in registers, unless their values may not fit in 64 bits
+/// - the other signals are shown in binary
+///
+/// ```
+/// use rata_wave::display::{DisplayMode, default_mode};
+/// use rata_wave::signal::Signal;
+/// use vcd::{Var, VarType};
+///
+/// let mode = |var_type, width, name: &str| {
+///     default_mode(&Signal::from_var(&Var::new(var_type, width, 0u32.into(), name.to_string(), None)))
+/// };
+/// assert_eq!(mode(VarType::Integer, 32, "count"), DisplayMode::SignedDecimal);
+/// assert_eq!(mode(VarType::Time, 64, "t"), DisplayMode::Decimal);
+/// assert_eq!(mode(VarType::Reg, 4, "mem_addr"), DisplayMode::Hex);
+/// assert_eq!(mode(VarType::Wire, 32, "data"), DisplayMode::Hex);
+/// assert_eq!(mode(VarType::Reg, 16, "cycles"), DisplayMode::Decimal);
+/// assert_eq!(mode(VarType::Reg, 80, "wide"), DisplayMode::Hex);
+/// assert_eq!(mode(VarType::Reg, 4, "state"), DisplayMode::Binary);
+/// assert_eq!(mode(VarType::Wire, 1, "clk"), DisplayMode::Binary);
+/// ```
+pub fn default_mode(signal: &Signal) -> DisplayMode {
+    let name = signal.name.to_lowercase();
+    let address = name.contains("addr") || name.ends_with("ptr") || name == "pc";
+    match signal.var_type {
+        VarType::Integer => DisplayMode::SignedDecimal,
+        VarType::Parameter | VarType::Time => DisplayMode::Decimal,
+        _ if signal.width > 1 && address => DisplayMode::Hex,
+        VarType::Wire if signal.width > WIDE_VECTOR => DisplayMode::Hex,
+        _ if signal.width > 64 => DisplayMode::Hex,
+        _ if signal.width > WIDE_VECTOR => DisplayMode::Decimal,
+        _ => DisplayMode::default(),
+    }
+}
+
+/// A row of the waveform area
+/// - `Signal` - a displayed signal
+/// - `Divider` - a labeled separator grouping the signals below it
+pub enum DisplayItem {
+    Signal(DisplayedSignal),
+    Divider(String),
+}
+
+/// Map the values of the columns to heights in `1..=levels` for the analog mode
+/// The heights are scaled between the minimum and the maximum of the given values
+/// Columns without a value (e.g. containing `x` or `z`) are kept as None
+///
+/// ```
+/// use rata_wave::display::analog_levels;
+///
+/// let levels = analog_levels(&[Some(0), Some(5), None, Some(10)], 24);
+/// assert_eq!(levels, vec![Some(1), Some(12), None, Some(24)]);
+///
+/// // A constant value is drawn at the bottom
+/// assert_eq!(analog_levels(&[Some(3), Some(3)], 24), vec![Some(1), Some(1)]);
+/// ```
+pub fn analog_levels(values: &[Option<u64>], levels: usize) -> Vec<Option<usize>> {
+    let min = values.iter().flatten().min().copied();
+    let max = values.iter().flatten().max().copied();
+    values
+        .iter()
+        .map(|value| {
+            let (value, min, max) = ((*value)?, min?, max?);
+            if max == min {
+                return Some(1);
+            }
+            let scaled =
+                (value - min) as u128 * levels.saturating_sub(1) as u128 / (max - min) as u128;
+            Some(1 + scaled as usize)
+        })
+        .collect()
+}
@@ -0,0 +1,67 @@
+use std::{error::Error, fmt::Display, io};
+
+/// Error met while loading a waveform file
+/// - `Io` - the file could not be read
+/// - `VcdParse` - the VCD file is malformed, with the line of the error
+/// - `NoTimescale` - the VCD file has no `$timescale`, only reported as a warning since
+///   `DEFAULT_TIMESCALE` is assumed instead
+/// - `UnsupportedFeature` - the file uses something RataWave cannot show
+/// - `UnknownScope` - the module to load, given by its path, is not in the file
+#[derive(Debug)]
+pub enum WaveError {
+    Io(io::Error),
+    VcdParse(vcd::ParseError),
+    NoTimescale,
+    UnsupportedFeature(String),
+    UnknownScope(String),
+}
+
+impl Display for WaveError {
+    /// ```
+    /// let error = rata_wave::load("assets/verilog/bad_header.vcd").err().unwrap();
+    /// assert!(matches!(error, rata_wave::error::WaveError::VcdParse(_)));
+    /// assert_eq!(error.to_string(), "Invalid VCD file: invalid digit found in string at line 5");
+    ///
+    /// let error = rata_wave::load("assets/verilog/missing.vcd").err().unwrap();
+    /// assert!(matches!(error, rata_wave::error::WaveError::Io(_)));
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaveError::Io(e) => write!(f, "{}", e),
+            WaveError::VcdParse(e) => write!(f, "Invalid VCD file: {}", e),
+            WaveError::NoTimescale => write!(
+                f,
+                "No timescale in the file, assuming 1{}",
+                crate::utils::DEFAULT_TIMESCALE
+            ),
+            WaveError::UnsupportedFeature(feature) => write!(f, "Unsupported: {}", feature),
+            WaveError::UnknownScope(scope) => write!(f, "No scope {}", scope),
+        }
+    }
+}
+
+impl Error for WaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaveError::Io(e) => Some(e),
+            WaveError::VcdParse(e) => Some(e),
+            WaveError::NoTimescale
+            | WaveError::UnsupportedFeature(_)
+            | WaveError::UnknownScope(_) => None,
+        }
+    }
+}
+
+/// The VCD parser reports its errors as `io::Error`s wrapping a `vcd::ParseError`, which are
+/// unwrapped into `VcdParse`
+impl From<io::Error> for WaveError {
+    fn from(e: io::Error) -> Self {
+        match e
+            .get_ref()
+            .and_then(|x| x.downcast_ref::<vcd::ParseError>())
+        {
+            Some(parse_error) => WaveError::VcdParse(parse_error.clone()),
+            None => WaveError::Io(e),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::io::{self, Write};
+
+use vcd::{TimescaleUnit, Value};
+
+use super::{
+    display::DisplayedSignal,
+    signal::{DisplayEvent, ValueDisplayEvent, VectorDisplayEvent},
+    time::Time,
+};
+
+const NAME_WIDTH: f64 = 160.0;
+const COLUMN_WIDTH: f64 = 8.0;
+const AXIS_HEIGHT: f64 = 30.0;
+const ROW_HEIGHT: f64 = 40.0;
+const WAVE_HEIGHT: f64 = 24.0;
+
+const BACKGROUND: &str = "#eff1f5";
+const TEXT: &str = "#4c4f69";
+const GRID: &str = "#ccd0da";
+const WAVE: &str = "#40a02b";
+const ERROR: &str = "#d20f39";
+
+/// Largest number of rows of an exported CSV, e.g. between markers far apart at a small step
+pub const MAX_CSV_ROWS: usize = 1_000_000;
+
+/// Render the given rows of display events as an SVG image
+/// Each row is the name of a signal and its events, as given by `Signal::events_arr_in_range`
+/// - `time_start` - the time of the first column in fs
+/// - `time_step` - the time of a column in fs
+///
+/// ```
+/// use rata_wave::export::view_to_svg;
+/// use rata_wave::signal::{Signal, ValueType};
+/// use vcd::{Value, Var, VarType};
+///
+/// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+/// let mut clk = Signal::from_var(&var);
+/// clk.add_event(0, ValueType::Value(Value::V0));
+/// clk.add_event(10, ValueType::Value(Value::V1));
+///
+/// let rows = vec![(clk.output_name(), clk.events_arr_in_range(0, 5, 4))];
+/// let svg = view_to_svg(&rows, 0, 5);
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains(">clk(!)</text>"));
+/// assert!(svg.trim_end().ends_with("</svg>"));
+/// ```
+pub fn view_to_svg(
+    rows: &[(String, Vec<DisplayEvent>)],
+    time_start: u64,
+    time_step: u64,
+) -> String {
+    let arr_size = rows.iter().map(|(_, x)| x.len()).max().unwrap_or(0);
+    let width = NAME_WIDTH + arr_size as f64 * COLUMN_WIDTH;
+    let height = AXIS_HEIGHT + rows.len() as f64 * ROW_HEIGHT;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"10\">\n",
+        width, height
+    );
+    svg += &format!(
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        BACKGROUND
+    );
+
+    // Time axis, with a tick after each 10 columns as in the terminal
+    for column in (0..arr_size).step_by(10) {
+        let x = NAME_WIDTH + column as f64 * COLUMN_WIDTH;
+        let time = Time::new(
+            time_start.saturating_add((column as u64).saturating_mul(time_step)),
+            TimescaleUnit::FS,
+        );
+        svg += &line(x, AXIS_HEIGHT - 8.0, x, height, GRID);
+        svg += &text(x + 2.0, AXIS_HEIGHT - 12.0, &time.to_string(), "start");
+    }
+
+    for (index, (name, events)) in rows.iter().enumerate() {
+        let top = AXIS_HEIGHT + index as f64 * ROW_HEIGHT + (ROW_HEIGHT - WAVE_HEIGHT) / 2.0;
+        let bottom = top + WAVE_HEIGHT;
+        let middle = top + WAVE_HEIGHT / 2.0;
+        svg += &text(4.0, middle + 4.0, name, "start");
+
+        let mut last_y = None;
+        for (column, event) in events.iter().enumerate() {
+            let x0 = NAME_WIDTH + column as f64 * COLUMN_WIDTH;
+            let x1 = x0 + COLUMN_WIDTH;
+            let color = if event.contains_x_or_z() { ERROR } else { WAVE };
+            match event {
+                DisplayEvent::Value(value_display_event) => {
+                    use ValueDisplayEvent::*;
+                    let (ChangeEvent(value) | MultipleEvent(value) | Stay(value)) =
+                        value_display_event;
+                    let y = match value {
+                        Value::V1 => top,
+                        Value::V0 => bottom,
+                        Value::X | Value::Z => middle,
+                    };
+                    if let MultipleEvent(_) = value_display_event {
+                        svg += &line(x0, top, x0, bottom, color);
+                    } else if let Some(last_y) = last_y
+                        && last_y != y
+                    {
+                        svg += &line(x0, last_y, x0, y, color);
+                    }
+                    svg += &line(x0, y, x1, y, color);
+                    last_y = Some(y);
+                }
+                DisplayEvent::Vector(vector_display_event) => match vector_display_event {
+                    VectorDisplayEvent::ChangeEvent(_) => {
+                        svg += &line(x0, top, x1, bottom, color);
+                        svg += &line(x0, bottom, x1, top, color);
+                    }
+                    VectorDisplayEvent::MultipleEvent(_) => {
+                        svg += &format!(
+                            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                            x0, top, COLUMN_WIDTH, WAVE_HEIGHT, color
+                        );
+                    }
+                    VectorDisplayEvent::Stay(_) => {
+                        svg += &line(x0, top, x1, top, color);
+                        svg += &line(x0, bottom, x1, bottom, color);
+                    }
+                },
+            }
+        }
+
+        // Values of vectors in the middle of each run of columns without a change
+        let mut run_start = 0;
+        for column in 0..=events.len() {
+            let run_ends = match events.get(column) {
+                Some(DisplayEvent::Vector(VectorDisplayEvent::Stay(_))) => false,
+                Some(DisplayEvent::Vector(_)) | None => true,
+                Some(DisplayEvent::Value(_)) => break,
+            };
+            if !run_ends {
+                continue;
+            }
+            if let Some(DisplayEvent::Vector(VectorDisplayEvent::Stay(vector))) =
+                events.get(run_start)
+            {
+                let label = vector.to_string();
+                let run_width = (column - run_start) as f64 * COLUMN_WIDTH;
+                // A character of the 10px monospace font is about 6px wide
+                if label.len() as f64 * 6.0 <= run_width {
+                    let x = NAME_WIDTH + run_start as f64 * COLUMN_WIDTH + run_width / 2.0;
+                    svg += &text(x, middle + 4.0, &label, "middle");
+                }
+            }
+            run_start = column + 1;
+        }
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+/// Write the given signals sampled as a CSV time series to `out`
+/// The first column is the time in fs, followed by a column per signal with the value in effect
+/// at that time, vectors are written in the display mode of their signal (see
+/// `DisplayedSignal::format_value`) and `x`/`z` are kept as is
+/// A value is empty before the first event of its signal
+/// - `time_start` - the time of the first row in fs
+/// - `time_step` - the time between two rows in fs
+/// - `rows` - the number of rows
+///
+/// ```
+/// use rata_wave::display::{DisplayMode, DisplayedSignal};
+/// use rata_wave::export::write_csv;
+/// use rata_wave::signal::{Signal, ValueType};
+/// use std::{cell::RefCell, rc::Rc};
+/// use vcd::{Value, Var, VarType};
+///
+/// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+/// let mut clk = Signal::from_var(&var);
+/// clk.add_event(0, ValueType::Value(Value::V0));
+/// clk.add_event(10, ValueType::Value(Value::X));
+/// let clk = DisplayedSignal::new(Rc::new(RefCell::new(clk)));
+///
+/// let var = Var::new(VarType::Wire, 8, 1u32.into(), "bus".to_string(), None);
+/// let mut bus = Signal::from_var(&var);
+/// bus.add_event(5, ValueType::Vector("z".parse().unwrap()));
+/// bus.add_event(10, ValueType::Vector("11110110".parse().unwrap()));
+/// let mut bus = DisplayedSignal::new(Rc::new(RefCell::new(bus)));
+///
+/// let signals = [("clk".to_string(), &clk), ("bus".to_string(), &bus)];
+/// let mut csv = Vec::new();
+/// write_csv(&mut csv, &signals, 0, 5, 3).unwrap();
+/// assert_eq!(csv, b"time_fs,clk,bus\n0,0,\n5,0,zzzzzzzz\n10,x,11110110\n");
+///
+/// bus.mode = DisplayMode::Hex;
+/// let signals = [("clk".to_string(), &clk), ("bus".to_string(), &bus)];
+/// let mut csv = Vec::new();
+/// write_csv(&mut csv, &signals, 10, 5, 1).unwrap();
+/// assert_eq!(csv, b"time_fs,clk,bus\n10,x,f6\n");
+/// ```
+pub fn write_csv(
+    out: &mut impl Write,
+    signals: &[(String, &DisplayedSignal)],
+    time_start: u64,
+    time_step: u64,
+    rows: usize,
+) -> io::Result<()> {
+    write!(out, "time_fs")?;
+    for (name, _) in signals {
+        write!(out, ",{}", escape_csv(name))?;
+    }
+    writeln!(out)?;
+
+    for row in 0..rows {
+        let time = time_start.saturating_add((row as u64).saturating_mul(time_step));
+        write!(out, "{}", time)?;
+        for (_, signal) in signals {
+            write!(out, ",{}", signal.value_at(time).unwrap_or_default())?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a comma, a quote or a line break
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn line(x0: f64, y0: f64, x1: f64, y1: f64, color: &str) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"/>\n",
+        x0, y0, x1, y1, color
+    )
+}
+
+fn text(x: f64, y: f64, content: &str, anchor: &str) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" fill=\"{}\" text-anchor=\"{}\">{}</text>\n",
+        x,
+        y,
+        TEXT,
+        anchor,
+        escape_xml(content)
+    )
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+/// Undo and redo stacks of snapshots of a state
+/// At most `capacity` snapshots are kept to undo, the oldest ones are dropped first
+///
+/// ```
+/// use rata_wave::history::History;
+///
+/// let mut history = History::new(2);
+/// let mut state = 0;
+/// for new_state in 1..=3 {
+///     history.record(state);
+///     state = new_state;
+/// }
+///
+/// state = history.undo(state).unwrap();
+/// assert_eq!(state, 2);
+/// state = history.undo(state).unwrap();
+/// assert_eq!(state, 1);
+/// // The snapshot of 0 was dropped
+/// assert_eq!(history.undo(state), None);
+///
+/// state = history.redo(state).unwrap();
+/// assert_eq!(state, 2);
+/// // A new change clears the snapshots to redo
+/// history.record(state);
+/// assert_eq!(history.redo(5), None);
+/// ```
+pub struct History<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    capacity: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            undo: VecDeque::new(),
+            redo: vec![],
+            capacity,
+        }
+    }
+
+    /// Record the snapshot of the state before a change
+    pub fn record(&mut self, snapshot: T) {
+        self.redo.clear();
+        self.undo.push_back(snapshot);
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Return the state before the last change, given the current state
+    /// Return None if there is nothing to undo
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Return the state of the last undone change, given the current state
+    /// Return None if there is nothing to redo
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(next)
+    }
+}
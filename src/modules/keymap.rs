@@ -0,0 +1,327 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, io,
+    path::PathBuf,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use super::{config, ui::KEY_BINDINGS};
+
+/// Actions of the normal mode that can be bound to keys in the config file
+/// The name of an action in the config file is given by `Action::name`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    AddSignal,
+    Quit,
+    ZoomIn,
+    ZoomOut,
+    ZoomFit,
+    ScrollLeft,
+    ScrollRight,
+    GotoTime,
+    CenterTime,
+    SetStep,
+    CursorLeft,
+    CursorRight,
+    CopyValue,
+    MarkerA,
+    MarkerB,
+    FocusNext,
+    FocusPrev,
+    NextEdge,
+    PrevEdge,
+    NextChange,
+    PrevChange,
+    MoveDown,
+    MoveUp,
+    InsertDivider,
+    Rename,
+    ToggleAnalog,
+    CycleFormat,
+    ToggleGrid,
+    NextTheme,
+    SaveSession,
+    ExportSvg,
+    ExportCsv,
+    Command,
+    Help,
+    Undo,
+    Redo,
+    AddBookmark,
+    Bookmarks,
+    Reload,
+    Grow,
+    Shrink,
+    ToggleCompact,
+    ToggleDiff,
+    Info,
+    SetOrigin,
+    Clear,
+}
+
+impl Action {
+    /// Name of the action in the config file, e.g. `zoom_in`
+    pub fn name(self) -> &'static str {
+        use Action::*;
+        match self {
+            AddSignal => "add_signal",
+            Quit => "quit",
+            ZoomIn => "zoom_in",
+            ZoomOut => "zoom_out",
+            ZoomFit => "zoom_fit",
+            ScrollLeft => "scroll_left",
+            ScrollRight => "scroll_right",
+            GotoTime => "goto_time",
+            CenterTime => "center_time",
+            SetStep => "set_step",
+            CursorLeft => "cursor_left",
+            CursorRight => "cursor_right",
+            CopyValue => "copy_value",
+            MarkerA => "marker_a",
+            MarkerB => "marker_b",
+            FocusNext => "focus_next",
+            FocusPrev => "focus_prev",
+            NextEdge => "next_edge",
+            PrevEdge => "prev_edge",
+            NextChange => "next_change",
+            PrevChange => "prev_change",
+            MoveDown => "move_down",
+            MoveUp => "move_up",
+            InsertDivider => "insert_divider",
+            Rename => "rename",
+            ToggleAnalog => "toggle_analog",
+            CycleFormat => "cycle_format",
+            ToggleGrid => "toggle_grid",
+            NextTheme => "next_theme",
+            SaveSession => "save_session",
+            ExportSvg => "export_svg",
+            ExportCsv => "export_csv",
+            Command => "command",
+            Help => "help",
+            Undo => "undo",
+            Redo => "redo",
+            AddBookmark => "add_bookmark",
+            Bookmarks => "bookmarks",
+            Reload => "reload",
+            Grow => "grow",
+            Shrink => "shrink",
+            ToggleCompact => "toggle_compact",
+            ToggleDiff => "toggle_diff",
+            Info => "info",
+            SetOrigin => "set_origin",
+            Clear => "clear",
+        }
+    }
+
+    /// The action of the given name, see `Action::name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        KEY_BINDINGS
+            .iter()
+            .map(|(action, _, _)| *action)
+            .find(|x| x.name() == name)
+    }
+}
+
+/// A key, with whether Ctrl is held
+/// Other modifiers are not part of the key, e.g. Shift is already in the case of a character
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub ctrl: bool,
+}
+
+impl Key {
+    pub fn new(code: KeyCode) -> Self {
+        Key { code, ctrl: false }
+    }
+
+    /// The key of a key press
+    pub fn of(event: &KeyEvent) -> Self {
+        Key {
+            code: event.code,
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+        }
+    }
+}
+
+/// Parse the name of a key, i.e. a single character or one of `Left`, `Right`, `Up`, `Down`,
+/// `Home`, `End`, `PageUp`, `PageDown`, `Tab`, `Enter`, `Esc`, `Space` and `F1` to `F12`,
+/// optionally prefixed by `Ctrl-`
+///
+/// ```
+/// use crossterm::event::KeyCode;
+/// use rata_wave::keymap::{Key, parse_key};
+///
+/// assert_eq!(parse_key("h"), Some(Key::new(KeyCode::Char('h'))));
+/// assert_eq!(parse_key("Left"), Some(Key::new(KeyCode::Left)));
+/// assert_eq!(parse_key("F5"), Some(Key::new(KeyCode::F(5))));
+/// assert_eq!(parse_key("Space"), Some(Key::new(KeyCode::Char(' '))));
+/// let ctrl_r = Key { code: KeyCode::Char('r'), ctrl: true };
+/// assert_eq!(parse_key("Ctrl-r"), Some(ctrl_r));
+/// assert_eq!(parse_key("hl"), None);
+/// ```
+pub fn parse_key(name: &str) -> Option<Key> {
+    match name.strip_prefix("Ctrl-") {
+        Some(name) => Some(Key {
+            ctrl: true,
+            ..parse_key_code(name)?
+        }),
+        None => parse_key_code(name),
+    }
+}
+
+fn parse_key_code(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::new(KeyCode::Char(c)));
+    }
+    Some(Key::new(match name {
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Tab" => KeyCode::Tab,
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Space" => KeyCode::Char(' '),
+        _ => match name.strip_prefix('F')?.parse() {
+            Ok(n @ 1..=12) => KeyCode::F(n),
+            _ => return None,
+        },
+    }))
+}
+
+/// Name of a key as accepted by `parse_key`
+pub fn key_name(key: Key) -> String {
+    let name = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        code => format!("{:?}", code),
+    };
+    match key.ctrl {
+        true => format!("Ctrl-{}", name),
+        false => name,
+    }
+}
+
+/// Keys of an action in the config file, a single key or a list of keys
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default)]
+    keys: BTreeMap<String, Keys>,
+}
+
+/// The keys bound to the actions of the normal mode
+/// The default keys are listed in `ui::KEY_BINDINGS`
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    actions: HashMap<Key, Action>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap {
+            actions: KEY_BINDINGS
+                .iter()
+                .map(|(action, key, _)| (parse_key(key).unwrap(), *action))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// The action bound to the key of a key press
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.actions.get(&Key::of(event)).copied()
+    }
+
+    /// Keys bound to `action`, sorted by name
+    pub fn keys_of(&self, action: Action) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .actions
+            .iter()
+            .filter(|(_, x)| **x == action)
+            .map(|(key, _)| key_name(*key))
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Build the key map of a config file, the actions listed in its `[keys]` table replace
+    /// their default keys, and a key taken from another action is removed from it
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use rata_wave::keymap::{Action, KeyMap};
+    ///
+    /// let keymap = KeyMap::from_toml("[keys]\nzoom_in = \"+\"\nscroll_left = [\"h\", \"Left\"]")
+    ///     .unwrap();
+    /// let press = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    /// assert_eq!(keymap.action(&press(KeyCode::Char('+'))), Some(Action::ZoomIn));
+    /// assert_eq!(keymap.action(&press(KeyCode::Char('='))), None);
+    /// assert_eq!(keymap.action(&press(KeyCode::Left)), Some(Action::ScrollLeft));
+    /// assert!(keymap.keys_of(Action::CursorLeft).is_empty());
+    /// // Other actions keep their default keys
+    /// assert_eq!(keymap.action(&press(KeyCode::Char('-'))), Some(Action::ZoomOut));
+    ///
+    /// assert!(KeyMap::from_toml("[keys]\nzoom = \"+\"").is_err());
+    /// assert!(KeyMap::from_toml("[keys]\nzoom_in = \"plus\"").is_err());
+    /// assert!(KeyMap::from_toml("[keys]\nzoom_in = \"z\"\nzoom_out = \"z\"").is_err());
+    /// ```
+    pub fn from_toml(content: &str) -> Result<KeyMap, String> {
+        let config: Config = toml::from_str(content).map_err(|e| e.to_string())?;
+        let mut keymap = KeyMap::default();
+        let mut bound = HashMap::new();
+        for (name, keys) in config.keys {
+            let action =
+                Action::from_name(&name).ok_or_else(|| format!("Unknown action: {}", name))?;
+            let keys = match keys {
+                Keys::One(key) => vec![key],
+                Keys::Many(keys) => keys,
+            };
+            keymap.actions.retain(|_, x| *x != action);
+            for key in keys {
+                let parsed = parse_key(&key).ok_or_else(|| format!("Unknown key: {}", key))?;
+                if let Some(other) = bound.insert(parsed, action) {
+                    return Err(format!(
+                        "Key {} is bound to both {} and {}",
+                        key,
+                        other.name(),
+                        action.name()
+                    ));
+                }
+            }
+        }
+        keymap.actions.extend(bound);
+        Ok(keymap)
+    }
+
+    /// Path of the config file, i.e. `ratawave/config.toml` in `$XDG_CONFIG_HOME`, or in
+    /// `~/.config` when it is not set
+    pub fn config_path() -> Option<PathBuf> {
+        let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_dir.join("ratawave").join("config.toml"))
+    }
+
+    /// Load the key map of the config file, or the default one if there is no config file
+    pub fn load() -> io::Result<KeyMap> {
+        config::Config::load().map(|x| x.keymap)
+    }
+}
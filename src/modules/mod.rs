@@ -1,4 +1,15 @@
+pub mod check;
+pub mod command;
+pub mod config;
+pub mod display;
+pub mod error;
+pub mod export;
+pub mod history;
+pub mod keymap;
 pub mod module;
+pub mod session;
 pub mod signal;
+pub mod theme;
 pub mod time;
 pub mod ui;
+pub mod waveform;
@@ -12,8 +12,11 @@ use super::signal::{Signal, ValueType};
 
 /// A module struct representing modules in the VCD file.
 /// A root module is created to contain the top-level signals.
+/// Besides modules, it represents the other scopes of the file, i.e. tasks, functions, `begin`
+/// blocks and forks, told apart by `scope_type`
 pub struct Module {
     pub(crate) name: String,
+    pub(crate) scope_type: ScopeType,
     pub(crate) depth: u8,
     pub(crate) signals: Vec<Rc<RefCell<Signal>>>,
     pub(crate) submodules: Vec<Rc<RefCell<Module>>>,
@@ -21,10 +24,22 @@ pub struct Module {
 }
 
 impl Module {
-    /// Build a module from the scope
+    /// Build a module from the scope, of any scope type
     /// The parent of the module is set to None
+    ///
+    /// ```
+    /// use rata_wave::utils::parse_files;
+    /// use std::rc::Rc;
+    /// use vcd::ScopeType;
+    ///
+    /// let (root, _, _, _) = parse_files("assets/verilog/scope_types.vcd".to_string()).unwrap();
+    /// let top = Rc::clone(&root.borrow().submodules()[0]);
+    /// let scope_types: Vec<ScopeType> =
+    ///     top.borrow().submodules().iter().map(|x| x.borrow().scope_type()).collect();
+    /// assert_eq!(scope_types, vec![ScopeType::Function, ScopeType::Task, ScopeType::Begin]);
+    /// assert_eq!(root.borrow().get_signals().len(), 4);
+    /// ```
     pub fn from_scope(scope: &Scope, depth: u8) -> Rc<RefCell<Module>> {
-        assert!(scope.scope_type == ScopeType::Module);
         let mut signals = vec![];
         let mut sub_modules = vec![];
 
@@ -42,6 +57,7 @@ impl Module {
 
         let module = Rc::new(RefCell::new(Module {
             name: scope.identifier.clone(),
+            scope_type: scope.scope_type,
             depth,
             signals,
             submodules: sub_modules,
@@ -62,6 +78,15 @@ impl Module {
 
         module
     }
+
+    /// Type of the scope the module was built from, `Module` for the root module
+    pub fn scope_type(&self) -> ScopeType {
+        self.scope_type
+    }
+
+    pub fn submodules(&self) -> &[Rc<RefCell<Module>>] {
+        &self.submodules
+    }
 }
 impl fmt::Debug for Module {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -93,6 +118,23 @@ impl Display for Module {
 }
 
 impl Module {
+    /// Add an event to every signal with the code `id` in the module and its submodules
+    /// Signals sharing a code (aliases, e.g. a port and the net connected to it) each keep their
+    /// own copy of the events, which stay in sync
+    ///
+    /// ```
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// // `test_tb.clk` and `test_tb.i1.clk` are both declared with the code `"`
+    /// let outer = waveform.signal_by_path("test_tb:clk").unwrap();
+    /// let inner = waveform.signal_by_path("test_tb->i1:clk").unwrap();
+    /// assert_eq!(outer.borrow().code, inner.borrow().code);
+    /// assert!(!outer.borrow().events.is_empty());
+    /// assert_eq!(outer.borrow().events, inner.borrow().events);
+    /// assert_eq!(
+    ///     outer.borrow().events_str_in_range(0, 1000, 100),
+    ///     inner.borrow().events_str_in_range(0, 1000, 100)
+    /// );
+    /// ```
     pub fn add_event(&mut self, id: IdCode, timestamp: u64, value: ValueType) {
         self.signals
             .iter_mut()
@@ -115,6 +157,107 @@ impl Module {
         signal_vec
     }
 
+    /// All signals in the module and its submodules, paired with their paths
+    /// (see `Signal::output_path`)
+    ///
+    /// ```
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// let paths: Vec<String> = waveform
+    ///     .root()
+    ///     .borrow()
+    ///     .all_signals_with_paths()
+    ///     .into_iter()
+    ///     .map(|(path, _)| path)
+    ///     .collect();
+    /// assert!(paths.contains(&"test_tb:clk(\")".to_string()));
+    /// assert!(paths.contains(&"test_tb->i1:clk(\")".to_string()));
+    /// ```
+    pub fn all_signals_with_paths(&self) -> Vec<(String, Rc<RefCell<Signal>>)> {
+        self.get_signals()
+            .into_iter()
+            .map(|x| {
+                let path = x.borrow().output_path();
+                (path, x)
+            })
+            .collect()
+    }
+
+    /// Find a signal by its path below this module, e.g. `top->i1:clk` as given by
+    /// `Signal::output_path`, or `top.i1.clk`
+    /// The range and the code of the signal can be given, e.g. `top:bus[3:0](!)`, the code tells
+    /// apart signals of the same name, otherwise the first of them is returned
+    ///
+    /// ```
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// let root = waveform.root();
+    /// let root = root.borrow();
+    ///
+    /// let clk = root.find_by_path("test_tb->i1:clk").unwrap();
+    /// assert_eq!(clk.borrow().output_path(), "test_tb->i1:clk(\")");
+    /// let counter = root.find_by_path("test_tb.i1.counter").unwrap();
+    /// assert_eq!(counter.borrow().output_path(), "test_tb->i1:counter[3:0](%)");
+    /// // The outer counter has another code
+    /// let outer = root.find_by_path("test_tb:counter[3:0](!)").unwrap();
+    /// assert_eq!(outer.borrow().output_path(), "test_tb:counter[3:0](!)");
+    ///
+    /// assert!(root.find_by_path("test_tb:counter(%)").is_none());
+    /// assert!(root.find_by_path("test_tb.i2.clk").is_none());
+    /// assert!(root.find_by_path("clk").is_none());
+    /// ```
+    pub fn find_by_path(&self, path: &str) -> Option<Rc<RefCell<Signal>>> {
+        let (path, code) = match path.strip_suffix(')').and_then(|x| x.rsplit_once('(')) {
+            Some((path, code)) => (path, Some(code)),
+            None => (path, None),
+        };
+        let (path, range) = match path.strip_suffix(']').and_then(|x| x.rsplit_once('[')) {
+            Some((path, range)) => (path, Some(format!("[{}]", range))),
+            None => (path, None),
+        };
+
+        let path = path.replace("->", ".").replace(':', ".");
+        let (modules, name) = match path.rsplit_once('.') {
+            Some((modules, name)) => (Some(modules), name),
+            None => (None, path.as_str()),
+        };
+        let signals = match modules {
+            Some(modules) => self.find_module(modules)?.borrow().signals.clone(),
+            None => self.signals.clone(),
+        };
+        signals.into_iter().find(|x| {
+            let signal = x.borrow();
+            signal.name == name
+                && range.as_ref().is_none_or(|x| *x == signal.output_range())
+                && code.is_none_or(|x| x == signal.code.to_string())
+        })
+    }
+
+    /// Find a submodule by its path below this module, e.g. `top->i1` as given by
+    /// `Module::get_path_str`, or `top.i1`
+    ///
+    /// ```
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// let i1 = waveform.root().borrow().find_module("test_tb.i1").unwrap();
+    /// assert_eq!(rata_wave::module::Module::get_path_str(&i1), "test_tb->i1");
+    /// assert!(waveform.root().borrow().find_module("test_tb->i2").is_none());
+    /// ```
+    pub fn find_module(&self, path: &str) -> Option<Rc<RefCell<Module>>> {
+        let path = path.replace("->", ".");
+        let mut names = path.split('.');
+        let first = names.next()?;
+        let mut module = Rc::clone(self.submodules.iter().find(|x| x.borrow().name == first)?);
+        for name in names {
+            let submodule = Rc::clone(
+                module
+                    .borrow()
+                    .submodules
+                    .iter()
+                    .find(|x| x.borrow().name == name)?,
+            );
+            module = submodule;
+        }
+        Some(module)
+    }
+
     pub fn max_time(&self) -> u64 {
         let mut max_time = 0;
         self.signals.iter().for_each(|x| {
@@ -137,7 +280,11 @@ impl Module {
 
     pub fn get_path_str(s: &Rc<RefCell<Module>>) -> String {
         // Get the path of the module from the root
-        let mut path = vec![];
+        // The root module itself is not part of the path
+        if s.borrow().parent.is_none() {
+            return String::new();
+        }
+        let mut path = vec![s.borrow().name.clone()];
         let mut node = Rc::clone(s);
 
         while let Some(parent_weak) = {
@@ -162,3 +309,85 @@ impl Module {
         path.join("->")
     }
 }
+
+/// Builder of a module tree in memory, e.g. to test rendering and analyses without a waveform
+/// file
+///
+/// ```
+/// use rata_wave::{module::ModuleBuilder, signal::{Signal, ValueType}};
+/// use vcd::Value;
+///
+/// let clk = Signal::with_events("clk", 0u32.into(), vec![(0, ValueType::Value(Value::V0))]);
+/// let root = ModuleBuilder::root()
+///     .submodule(ModuleBuilder::new("top").submodule(ModuleBuilder::new("cpu").signal(clk)))
+///     .build();
+///
+/// let clk = root.borrow().find_by_path("top->cpu:clk").unwrap();
+/// assert_eq!(clk.borrow().output_path(), "top->cpu:clk(!)");
+/// assert_eq!(root.borrow().max_time(), 0);
+/// ```
+pub struct ModuleBuilder {
+    name: String,
+    scope_type: ScopeType,
+    signals: Vec<Signal>,
+    submodules: Vec<ModuleBuilder>,
+}
+
+impl ModuleBuilder {
+    /// A module of the given name without signals
+    pub fn new(name: &str) -> Self {
+        ModuleBuilder {
+            name: name.to_string(),
+            scope_type: ScopeType::Module,
+            signals: vec![],
+            submodules: vec![],
+        }
+    }
+
+    /// The root module, containing the top-level modules and signals like the one of a parsed file
+    pub fn root() -> Self {
+        Self::new("Root")
+    }
+
+    pub fn scope_type(mut self, scope_type: ScopeType) -> Self {
+        self.scope_type = scope_type;
+        self
+    }
+
+    pub fn signal(mut self, signal: Signal) -> Self {
+        self.signals.push(signal);
+        self
+    }
+
+    pub fn submodule(mut self, submodule: ModuleBuilder) -> Self {
+        self.submodules.push(submodule);
+        self
+    }
+
+    /// Build the module tree, with the parents of the modules and signals set
+    pub fn build(self) -> Rc<RefCell<Module>> {
+        self.build_at(1)
+    }
+
+    fn build_at(self, depth: u8) -> Rc<RefCell<Module>> {
+        let module = Rc::new(RefCell::new(Module {
+            name: self.name,
+            scope_type: self.scope_type,
+            depth,
+            signals: vec![],
+            submodules: vec![],
+            parent: None,
+        }));
+        for signal in self.signals {
+            let signal = Rc::new(RefCell::new(signal));
+            signal.borrow_mut().parent_module = Some(Rc::downgrade(&module));
+            module.borrow_mut().signals.push(signal);
+        }
+        for submodule in self.submodules {
+            let submodule = submodule.build_at(depth + 1);
+            submodule.borrow_mut().parent = Some(Rc::downgrade(&module));
+            module.borrow_mut().submodules.push(submodule);
+        }
+        module
+    }
+}
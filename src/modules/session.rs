@@ -0,0 +1,86 @@
+use std::{collections::BTreeMap, fs, io, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::display::DisplayMode;
+
+/// A row of the displayed list saved to a session file
+/// A signal is saved as its path, so that the sessions saved before dividers still load
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum SessionItem {
+    /// Path of a displayed signal, as given by `Signal::output_path`
+    Signal(String),
+    /// Label of a divider
+    Divider { divider: String },
+}
+
+/// The view state saved to and restored from a session file
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct Session {
+    /// Displayed signals and dividers, in their order
+    #[serde(alias = "signals")]
+    pub items: Vec<SessionItem>,
+    /// Start time of the view in fs
+    pub time_start: u64,
+    /// Time step of the view in fs
+    pub time_step: u64,
+    /// Aliases of the displayed signals, keyed by their paths
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Display modes of the displayed signals, keyed by their paths
+    #[serde(default)]
+    pub modes: BTreeMap<String, DisplayMode>,
+    /// Value maps of the displayed vectors, keyed by their paths
+    #[serde(default)]
+    pub value_maps: BTreeMap<String, Vec<(u64, String)>>,
+    /// Times in fs and labels of the bookmarks
+    #[serde(default)]
+    pub bookmarks: Vec<(u64, String)>,
+}
+
+impl Session {
+    /// Path of the session file belonging to a waveform file, e.g. `test.vcd` -> `test.ratawave`
+    pub fn path_for(waveform_path: &str) -> PathBuf {
+        Path::new(waveform_path).with_extension("ratawave")
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content =
+            toml::to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+
+    /// ```
+    /// use rata_wave::display::DisplayMode;
+    /// use rata_wave::session::{Session, SessionItem};
+    ///
+    /// let session = Session {
+    ///     items: vec![
+    ///         SessionItem::Divider { divider: "clocks".to_string() },
+    ///         SessionItem::Signal("test_tb:clk(\")".to_string()),
+    ///     ],
+    ///     time_start: 1000,
+    ///     time_step: 10,
+    ///     aliases: [("test_tb:clk(\")".to_string(), "clk".to_string())].into(),
+    ///     modes: [("test_tb:state(#)".to_string(), DisplayMode::Hex)].into(),
+    ///     value_maps: [("test_tb:state(#)".to_string(), vec![(0, "IDLE".to_string())])].into(),
+    ///     bookmarks: vec![(500, "reset done".to_string())],
+    /// };
+    /// let path = std::env::temp_dir().join("rata_wave_doctest.ratawave");
+    /// session.save(&path).unwrap();
+    /// assert_eq!(Session::load(&path).unwrap(), session);
+    ///
+    /// // Sessions saved before dividers list the paths of the signals only
+    /// let content = r#"signals = ["test_tb:clk(\")"]
+    /// time_start = 0
+    /// time_step = 10"#;
+    /// std::fs::write(&path, content).unwrap();
+    /// let items = Session::load(&path).unwrap().items;
+    /// assert_eq!(items, [SessionItem::Signal("test_tb:clk(\")".to_string())]);
+    /// ```
+    pub fn load(path: &Path) -> io::Result<Session> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
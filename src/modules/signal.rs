@@ -1,9 +1,10 @@
 use core::{fmt, panic};
-use std::{cell::RefCell, fmt::Display, rc::Weak};
+use std::{cell::RefCell, fmt::Display, rc::Weak, str::FromStr};
 
-use vcd::{IdCode, Value, Var, Vector};
+use vcd::{IdCode, ReferenceIndex, Value, Var, VarType, Vector};
 
 use super::module::Module;
+use crate::utils::vector_contain_x_or_z;
 
 /// Type of the signal
 /// - `Value`: the signal has only one bit
@@ -14,17 +15,19 @@ pub enum ValueType {
     Vector(Vector),
 }
 
+/// - `MultipleEvent`: several events in one time step, holding the value after the last one
 #[derive(Clone, Debug)]
 pub enum ValueDisplayEvent {
     ChangeEvent(Value),
-    MultipleEvent,
+    MultipleEvent(Value),
     Stay(Value),
 }
 
+/// - `MultipleEvent`: several events in one time step, holding the value after the last one
 #[derive(Clone, Debug)]
 pub enum VectorDisplayEvent {
     ChangeEvent(Vector),
-    MultipleEvent,
+    MultipleEvent(Vector),
     Stay(Vector),
 }
 
@@ -40,27 +43,187 @@ impl PartialEq<ValueType> for DisplayEvent {
             ValueType::Value(value) => match self {
                 DisplayEvent::Value(ValueDisplayEvent::Stay(v)) => *v == *value,
                 DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(v)) => *v == *value,
+                DisplayEvent::Value(ValueDisplayEvent::MultipleEvent(v)) => *v == *value,
                 _ => false,
             },
             ValueType::Vector(vector) => match self {
                 DisplayEvent::Vector(VectorDisplayEvent::Stay(v)) => *v == *vector,
                 DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(v)) => *v == *vector,
+                DisplayEvent::Vector(VectorDisplayEvent::MultipleEvent(v)) => *v == *vector,
                 _ => false,
             },
         }
     }
 }
 
+impl DisplayEvent {
+    /// The `Stay` event holding the value this event ends with
+    fn to_stay(&self) -> DisplayEvent {
+        use DisplayEvent::*;
+        match self {
+            Value(ValueDisplayEvent::ChangeEvent(v))
+            | Value(ValueDisplayEvent::MultipleEvent(v))
+            | Value(ValueDisplayEvent::Stay(v)) => Value(ValueDisplayEvent::Stay(*v)),
+            Vector(VectorDisplayEvent::ChangeEvent(v))
+            | Vector(VectorDisplayEvent::MultipleEvent(v))
+            | Vector(VectorDisplayEvent::Stay(v)) => Vector(VectorDisplayEvent::Stay(v.clone())),
+        }
+    }
+
+    /// Check if the value of the event contains `x` or `z`
+    ///
+    /// ```
+    /// use rata_wave::signal::{DisplayEvent, VectorDisplayEvent};
+    ///
+    /// let vector = "1x0".parse().unwrap();
+    /// let event = DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(vector));
+    /// assert!(event.contains_x_or_z());
+    ///
+    /// let vector = "100".parse().unwrap();
+    /// let event = DisplayEvent::Vector(VectorDisplayEvent::MultipleEvent(vector));
+    /// assert!(!event.contains_x_or_z());
+    /// ```
+    pub fn contains_x_or_z(&self) -> bool {
+        use DisplayEvent::*;
+        match self {
+            Value(ValueDisplayEvent::ChangeEvent(v))
+            | Value(ValueDisplayEvent::MultipleEvent(v))
+            | Value(ValueDisplayEvent::Stay(v)) => matches!(v, vcd::Value::X | vcd::Value::Z),
+            Vector(VectorDisplayEvent::ChangeEvent(v))
+            | Vector(VectorDisplayEvent::MultipleEvent(v))
+            | Vector(VectorDisplayEvent::Stay(v)) => vector_contain_x_or_z(v),
+        }
+    }
+}
+
 /// Convert a `Vector` value to its decimal value
-/// Return None if the vector contains `x` or `z`
+/// Return None if the vector contains `x` or `z`, or if its value does not fit in 64 bits
+/// The first bit is the most significant one, whatever the direction of the declared range: as
+/// in Verilog, the left index of `[0:7]` is the MSB, and VCD writes the bits from the left index
+///
+/// ```
+/// use rata_wave::signal::vector_to_base_10;
+///
+/// // Both for a bus declared [7:0] and [0:7]
+/// assert_eq!(vector_to_base_10(&"00000110".parse().unwrap()), Some(6));
+/// assert_eq!(vector_to_base_10(&"0x".parse().unwrap()), None);
+///
+/// // Leading zeros do not count towards the 64 bits
+/// let wide = format!("{}101", "0".repeat(77));
+/// assert_eq!(vector_to_base_10(&wide.parse().unwrap()), Some(5));
+/// let wide = format!("1{}", "0".repeat(79));
+/// assert_eq!(vector_to_base_10(&wide.parse().unwrap()), None);
+/// ```
 pub fn vector_to_base_10(vector: &Vector) -> Option<u64> {
-    vector.iter().try_fold(0, |acc, value| match value {
-        Value::V0 => Some(acc * 2),
-        Value::V1 => Some(acc * 2 + 1),
-        _ => None,
+    vector.iter().try_fold(0u64, |acc, value| {
+        let bit = match value {
+            Value::V0 => 0,
+            Value::V1 => 1,
+            _ => return None,
+        };
+        acc.checked_mul(2)?.checked_add(bit)
     })
 }
 
+/// A value searched in the events of a signal
+/// - `Number` - a value without `x` or `z`, matching vectors of the same decimal value
+/// - `Bits` - a bit pattern, possibly with `x` or `z`, matching the same bits once both are
+///   extended to the width of the signal (see `extend_vector`)
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValuePattern {
+    Number(u64),
+    Bits(Vector),
+}
+
+impl ValuePattern {
+    /// Whether `value` of a signal of `width` bits matches the pattern
+    ///
+    /// ```
+    /// use rata_wave::signal::{ValuePattern, ValueType};
+    /// use vcd::Value;
+    ///
+    /// let five: ValuePattern = "5".parse().unwrap();
+    /// assert!(five.matches(&ValueType::Vector("0101".parse().unwrap()), 4));
+    /// assert!(five.matches(&ValueType::Vector("101".parse().unwrap()), 8));
+    /// assert!(!five.matches(&ValueType::Vector("x101".parse().unwrap()), 4));
+    ///
+    /// let pattern: ValuePattern = "0bx1".parse().unwrap();
+    /// assert!(pattern.matches(&ValueType::Vector("xxx1".parse().unwrap()), 4));
+    /// assert!(!pattern.matches(&ValueType::Vector("0001".parse().unwrap()), 4));
+    /// assert!("0bz".parse::<ValuePattern>().unwrap().matches(&ValueType::Value(Value::Z), 1));
+    ///
+    /// assert_eq!("0x1f".parse(), Ok(ValuePattern::Number(31)));
+    /// assert!("0b102".parse::<ValuePattern>().is_err());
+    /// ```
+    pub fn matches(&self, value: &ValueType, width: usize) -> bool {
+        match (self, value) {
+            (ValuePattern::Number(n), ValueType::Value(value)) => {
+                (*n == 0 && *value == Value::V0) || (*n == 1 && *value == Value::V1)
+            }
+            (ValuePattern::Number(n), ValueType::Vector(vector)) => {
+                vector_to_base_10(vector) == Some(*n)
+            }
+            (ValuePattern::Bits(bits), ValueType::Value(value)) => {
+                extend_vector(bits, 1).iter().eq([*value])
+            }
+            (ValuePattern::Bits(bits), ValueType::Vector(vector)) => {
+                extend_vector(bits, width) == extend_vector(vector, width)
+            }
+        }
+    }
+}
+
+impl FromStr for ValuePattern {
+    type Err = String;
+
+    /// Parse a decimal or `0x` value as a number, and a `0b` value as a bit pattern
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("Invalid value: {}", s);
+        if let Some(bits) = s.strip_prefix("0b") {
+            return bits.parse().map(ValuePattern::Bits).map_err(|_| invalid());
+        }
+        let number = match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse(),
+        };
+        number.map(ValuePattern::Number).map_err(|_| invalid())
+    }
+}
+
+impl Display for ValuePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValuePattern::Number(n) => write!(f, "{}", n),
+            ValuePattern::Bits(bits) => write!(f, "0b{}", bits),
+        }
+    }
+}
+
+/// Extend a vector to `width` bits as VCD does for values written with fewer bits
+/// The added high bits are `x` or `z` if the highest given bit is, and `0` otherwise
+/// A vector at least `width` bits wide is returned as is
+///
+/// ```
+/// use rata_wave::signal::extend_vector;
+///
+/// let extend = |vector: &str, width| extend_vector(&vector.parse().unwrap(), width).to_string();
+/// assert_eq!(extend("101", 8), "00000101");
+/// assert_eq!(extend("x1", 4), "xxx1");
+/// assert_eq!(extend("z", 4), "zzzz");
+/// assert_eq!(extend("1010", 2), "1010");
+/// ```
+pub fn extend_vector(vector: &Vector, width: usize) -> Vector {
+    let fill = match vector.get(0) {
+        Some(Value::X) => Value::X,
+        Some(Value::Z) => Value::Z,
+        _ => Value::V0,
+    };
+    std::iter::repeat_n(fill, width.saturating_sub(vector.len()))
+        .chain(vector.iter())
+        .collect()
+}
+
 impl Display for ValueType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -77,6 +240,15 @@ pub struct Signal {
     // reference string in vcd file
     pub code: IdCode,
     pub name: String,
+    // number of bits declared in the $var
+    pub width: u32,
+    // bit index or range declared in the $var, e.g. [31:0] or [0:31]
+    pub index: Option<ReferenceIndex>,
+    // type declared in the $var, e.g. wire, reg or parameter
+    pub var_type: VarType,
+    // (timestamp in fs, value), sorted by timestamp
+    // Events at the same time (delta cycles) are in the order of the file, the last one being the
+    // settled value
     pub events: Vec<(u64, ValueType)>,
     pub parent_module: Option<Weak<RefCell<Module>>>,
 }
@@ -86,14 +258,586 @@ impl Signal {
         Signal {
             code: var.code,
             name: var.reference.clone(),
+            width: var.size,
+            index: var.index,
+            var_type: var.var_type,
             events: vec![],
             parent_module: None,
         }
     }
 
+    /// Build a wire from its events, e.g. to test analyses without a waveform file
+    /// The width is the one of the widest vector, or 1 for scalars, and the events are sorted by
+    /// time
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::Value;
+    ///
+    /// let bus = Signal::with_events(
+    ///     "bus",
+    ///     1u32.into(),
+    ///     vec![
+    ///         (10, ValueType::Vector("0011".parse().unwrap())),
+    ///         (0, ValueType::Value(Value::X)),
+    ///     ],
+    /// );
+    /// assert_eq!(bus.width, 4);
+    /// assert_eq!(bus.events[0], (0, ValueType::Vector("x".parse().unwrap())));
+    /// assert_eq!(bus.value_at(15), Some(ValueType::Vector("0011".parse().unwrap())));
+    /// ```
+    pub fn with_events(name: &str, code: IdCode, mut events: Vec<(u64, ValueType)>) -> Signal {
+        let width = events
+            .iter()
+            .filter_map(|(_, value)| match value {
+                ValueType::Vector(vector) => Some(vector.len() as u32),
+                ValueType::Value(_) => None,
+            })
+            .max()
+            .unwrap_or(1);
+        let var = Var::new(VarType::Wire, width, code, name.to_string(), None);
+        let mut signal = Signal::from_var(&var);
+        events.sort_by_key(|(time, _)| *time);
+        events
+            .into_iter()
+            .for_each(|(time, value)| signal.add_event(time, value));
+        signal
+    }
+
+    /// Whether the signal holds a number rather than a logic value, i.e. it is declared as an
+    /// `integer` or a `parameter`
+    ///
+    /// ```
+    /// use vcd::VarType;
+    ///
+    /// let waveform = rata_wave::load("assets/verilog/var_types.vcd").unwrap();
+    /// let var_type = |path| waveform.signal_by_path(path).unwrap().borrow().var_type;
+    /// assert_eq!(var_type("top:clk"), VarType::Wire);
+    /// assert_eq!(var_type("top:state"), VarType::Reg);
+    /// assert_eq!(var_type("top:count"), VarType::Integer);
+    /// assert_eq!(var_type("top:WIDTH"), VarType::Parameter);
+    ///
+    /// assert!(waveform.signal_by_path("top:count").unwrap().borrow().is_numeric());
+    /// assert!(!waveform.signal_by_path("top:state").unwrap().borrow().is_numeric());
+    /// ```
+    pub fn is_numeric(&self) -> bool {
+        matches!(self.var_type, VarType::Integer | VarType::Parameter)
+    }
+
+    /// Add an event at the end of the events
+    /// Some writers emit single-bit vectors for 1-bit vars and scalars for wider vars, so the
+    /// value is normalized after the width of the signal: a vector of a 1-bit signal is stored as
+    /// its lowest bit, and a scalar of a wider signal as a 1-bit vector, see `extend_vector`
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "en".to_string(), None);
+    /// let mut en = Signal::from_var(&var);
+    /// en.add_event(0, ValueType::Vector("1".parse().unwrap()));
+    /// assert_eq!(en.events[0].1, ValueType::Value(Value::V1));
+    ///
+    /// let var = Var::new(VarType::Wire, 4, 1u32.into(), "bus".to_string(), None);
+    /// let mut bus = Signal::from_var(&var);
+    /// bus.add_event(0, ValueType::Value(Value::X));
+    /// assert_eq!(bus.events[0].1, ValueType::Vector("x".parse().unwrap()));
+    /// ```
     pub fn add_event(&mut self, timestamp: u64, value: ValueType) {
+        let value = match value {
+            ValueType::Vector(vector) if self.width == 1 => match vector.iter().last() {
+                Some(value) => ValueType::Value(value),
+                None => ValueType::Vector(vector),
+            },
+            ValueType::Value(value) if self.width > 1 => {
+                ValueType::Vector(std::iter::once(value).collect())
+            }
+            value => value,
+        };
         self.events.push((timestamp, value));
     }
+
+    /// Value in effect at `time`, i.e. the value of the last event at or before it
+    /// Of several events at `time` (delta cycles), the last one is the settled value returned
+    /// Return None if `time` is before the first event
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// clk.add_event(10, ValueType::Value(Value::V0));
+    /// clk.add_event(20, ValueType::Value(Value::V1));
+    ///
+    /// // Exactly at an event
+    /// assert_eq!(clk.value_at(20), Some(ValueType::Value(Value::V1)));
+    /// // Between two events
+    /// assert_eq!(clk.value_at(15), Some(ValueType::Value(Value::V0)));
+    /// // Before the first event
+    /// assert_eq!(clk.value_at(5), None);
+    ///
+    /// // Delta cycles settle on the last value
+    /// clk.add_event(30, ValueType::Value(Value::V0));
+    /// clk.add_event(30, ValueType::Value(Value::X));
+    /// clk.add_event(30, ValueType::Value(Value::V1));
+    /// assert_eq!(clk.value_at(30), Some(ValueType::Value(Value::V1)));
+    /// assert_eq!(clk.value_at(29), Some(ValueType::Value(Value::V1)));
+    /// ```
+    pub fn value_at(&self, time: u64) -> Option<ValueType> {
+        let index = self.events.partition_point(|(t, _)| *t <= time);
+        index.checked_sub(1).map(|i| self.events[i].1.clone())
+    }
+
+    /// Value of the first event, e.g. the value after the reset of the simulation
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::Value;
+    ///
+    /// let events = vec![(10, ValueType::Value(Value::X)), (20, ValueType::Value(Value::V1))];
+    /// let rst = Signal::with_events("rst", 0u32.into(), events);
+    /// assert_eq!(rst.initial_value(), Some(&ValueType::Value(Value::X)));
+    /// assert_eq!(Signal::with_events("rst", 0u32.into(), vec![]).initial_value(), None);
+    /// ```
+    pub fn initial_value(&self) -> Option<&ValueType> {
+        self.events.first().map(|(_, value)| value)
+    }
+
+    /// Value the signal settles to after its last event, the last one of the events at the same
+    /// time, see `value_at`
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::Value;
+    ///
+    /// let events = vec![
+    ///     (10, ValueType::Value(Value::X)),
+    ///     (20, ValueType::Value(Value::V0)),
+    ///     (20, ValueType::Value(Value::V1)),
+    /// ];
+    /// let rst = Signal::with_events("rst", 0u32.into(), events);
+    /// assert_eq!(rst.last_value(), Some(&ValueType::Value(Value::V1)));
+    /// assert_eq!(rst.value_at(u64::MAX).as_ref(), rst.last_value());
+    /// assert_eq!(Signal::with_events("rst", 0u32.into(), vec![]).last_value(), None);
+    /// ```
+    pub fn last_value(&self) -> Option<&ValueType> {
+        self.events.last().map(|(_, value)| value)
+    }
+
+    /// Events with a time in `[start, end)`, in time order
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// for i in 0..10u64 {
+    ///     let value = if i % 2 == 0 { Value::V0 } else { Value::V1 };
+    ///     clk.add_event(i * 10, ValueType::Value(value));
+    /// }
+    ///
+    /// let times: Vec<u64> = clk.events_between(20, 50).map(|(time, _)| *time).collect();
+    /// assert_eq!(times, vec![20, 30, 40]);
+    /// assert_eq!(clk.events_between(21, 29).count(), 0);
+    /// assert_eq!(clk.events_between(50, 20).count(), 0);
+    /// ```
+    pub fn events_between(&self, start: u64, end: u64) -> impl Iterator<Item = &(u64, ValueType)> {
+        let index = self.events.partition_point(|(t, _)| *t < start);
+        self.events[index..]
+            .iter()
+            .take_while(move |(t, _)| *t < end)
+    }
+
+    /// Number of events with a time in `[start, end)`, found by binary search on both bounds
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// for i in 0..10u64 {
+    ///     let value = if i % 2 == 0 { Value::V0 } else { Value::V1 };
+    ///     clk.add_event(i * 10, ValueType::Value(value));
+    /// }
+    ///
+    /// assert_eq!(clk.transition_count_in_range(20, 50), 3);
+    /// assert_eq!(clk.transition_count_in_range(0, u64::MAX), 10);
+    /// assert_eq!(clk.transition_count_in_range(50, 20), 0);
+    /// ```
+    pub fn transition_count_in_range(&self, start: u64, end: u64) -> usize {
+        let first = self.events.partition_point(|(t, _)| *t < start);
+        let last = self.events.partition_point(|(t, _)| *t < end);
+        last.saturating_sub(first)
+    }
+
+    /// Whether the signal has events before `start` and at or after `end`, i.e. outside the
+    /// window `[start, end)`
+    /// The events are sorted, so only the first and the last ones are checked
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// clk.add_event(10, ValueType::Value(Value::V0));
+    /// clk.add_event(50, ValueType::Value(Value::V1));
+    ///
+    /// assert_eq!(clk.events_outside(0, 100), (false, false));
+    /// assert_eq!(clk.events_outside(20, 40), (true, true));
+    /// assert_eq!(clk.events_outside(20, 60), (true, false));
+    /// assert_eq!(clk.events_outside(0, 50), (false, true));
+    /// ```
+    pub fn events_outside(&self, start: u64, end: u64) -> (bool, bool) {
+        (
+            self.events.first().is_some_and(|(t, _)| *t < start),
+            self.events.last().is_some_and(|(t, _)| *t >= end),
+        )
+    }
+
+    /// Time of the first event strictly after `t`
+    /// Return None if there is no such event
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// clk.add_event(0, ValueType::Value(Value::V0));
+    /// clk.add_event(10, ValueType::Value(Value::V1));
+    ///
+    /// assert_eq!(clk.next_edge_after(0), Some(10));
+    /// assert_eq!(clk.next_edge_after(5), Some(10));
+    /// assert_eq!(clk.next_edge_after(10), None);
+    /// ```
+    pub fn next_edge_after(&self, t: u64) -> Option<u64> {
+        let index = self.events.partition_point(|(time, _)| *time <= t);
+        self.events.get(index).map(|(time, _)| *time)
+    }
+
+    /// Time of the first event strictly after `t` whose value matches `pattern`
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Reg, 4, 0u32.into(), "state".to_string(), None);
+    /// let mut state = Signal::from_var(&var);
+    /// for (time, value) in [(0, "xxxx"), (10, "0101"), (20, "0000"), (30, "0101")] {
+    ///     state.add_event(time, ValueType::Vector(value.parse().unwrap()));
+    /// }
+    ///
+    /// let five = "5".parse().unwrap();
+    /// assert_eq!(state.next_match_after(0, &five), Some(10));
+    /// assert_eq!(state.next_match_after(10, &five), Some(30));
+    /// assert_eq!(state.next_match_after(30, &five), None);
+    /// assert_eq!(state.prev_match_before(30, &five), Some(10));
+    /// assert_eq!(state.prev_match_before(20, &"0bx".parse().unwrap()), Some(0));
+    /// ```
+    pub fn next_match_after(&self, t: u64, pattern: &ValuePattern) -> Option<u64> {
+        let index = self.events.partition_point(|(time, _)| *time <= t);
+        self.events[index..]
+            .iter()
+            .find(|(_, value)| pattern.matches(value, self.width as usize))
+            .map(|(time, _)| *time)
+    }
+
+    /// Time of the last event strictly before `t` whose value matches `pattern`
+    pub fn prev_match_before(&self, t: u64, pattern: &ValuePattern) -> Option<u64> {
+        let index = self.events.partition_point(|(time, _)| *time < t);
+        self.events[..index]
+            .iter()
+            .rev()
+            .find(|(_, value)| pattern.matches(value, self.width as usize))
+            .map(|(time, _)| *time)
+    }
+
+    /// Time of the last event strictly before `t`
+    /// Return None if there is no such event
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// clk.add_event(0, ValueType::Value(Value::V0));
+    /// clk.add_event(10, ValueType::Value(Value::V1));
+    ///
+    /// assert_eq!(clk.prev_edge_before(10), Some(0));
+    /// assert_eq!(clk.prev_edge_before(15), Some(10));
+    /// assert_eq!(clk.prev_edge_before(0), None);
+    /// ```
+    pub fn prev_edge_before(&self, t: u64) -> Option<u64> {
+        let index = self.events.partition_point(|(time, _)| *time < t);
+        index.checked_sub(1).map(|i| self.events[i].0)
+    }
+
+    /// The value a single-bit signal returns to at the end of `[start, end)` after leaving it
+    /// within the window, i.e. a pulse narrower than the window such as 0→1→0
+    /// Return None for vectors, windows without a pulse, or pulses of `x` or `z`
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "req".to_string(), None);
+    /// let mut req = Signal::from_var(&var);
+    /// for (time, value) in [(0, Value::V0), (12, Value::V1), (14, Value::V0), (25, Value::V1)] {
+    ///     req.add_event(time, ValueType::Value(value));
+    /// }
+    /// req.add_event(27, ValueType::Value(Value::V0));
+    /// req.add_event(28, ValueType::Value(Value::V1));
+    ///
+    /// // Two transitions inside one step of 10
+    /// assert_eq!(req.glitch_between(10, 20), Some(Value::V0));
+    /// // The signal does not return to 0 within the window
+    /// assert_eq!(req.glitch_between(10, 13), None);
+    /// // 0→1→0→1 does not end where it started
+    /// assert_eq!(req.glitch_between(20, 30), None);
+    /// assert_eq!(req.glitch_between(26, 30), Some(Value::V1));
+    /// ```
+    pub fn glitch_between(&self, start: u64, end: u64) -> Option<Value> {
+        let first = self.events.partition_point(|(t, _)| *t < start);
+        let last = self.events.partition_point(|(t, _)| *t < end);
+        let before = &self.events[first.checked_sub(1)?].1;
+        let window = &self.events[first..last];
+        match (before, window.last()) {
+            (ValueType::Value(v @ (Value::V0 | Value::V1)), Some((_, after)))
+                if after == before && window.iter().any(|(_, x)| x != before) =>
+            {
+                Some(*v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the event at `index` changes the value, i.e. it is the first event or its value
+    /// differs from the one of the previous event
+    /// Writers may record the same value again, e.g. in `$dumpvars` or for aliases, and vectors
+    /// may be written with fewer bits, see `extend_vector`
+    fn is_value_change(&self, index: usize) -> bool {
+        let Some(previous) = index.checked_sub(1).map(|i| &self.events[i].1) else {
+            return true;
+        };
+        !same_value(previous, &self.events[index].1, self.width as usize)
+    }
+
+    /// Whether the value of the signal differs from the one of `other` at any time in
+    /// `[start, end)`, e.g. the same signal in two runs of a simulation
+    /// A signal without value yet differs from one with a value
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "ack".to_string(), None);
+    /// let (mut golden, mut dut) = (Signal::from_var(&var), Signal::from_var(&var));
+    /// for (time, value) in [(0, Value::V0), (20, Value::V1), (40, Value::V0)] {
+    ///     golden.add_event(time, ValueType::Value(value));
+    /// }
+    /// for (time, value) in [(0, Value::V0), (25, Value::V1), (40, Value::V0)] {
+    ///     dut.add_event(time, ValueType::Value(value));
+    /// }
+    ///
+    /// assert!(!golden.differs_between(&dut, 0, 20));
+    /// assert!(golden.differs_between(&dut, 20, 30));
+    /// assert!(golden.differs_between(&dut, 24, 25));
+    /// assert!(!golden.differs_between(&dut, 25, 100));
+    /// assert_eq!(golden.first_difference(&dut), Some(20));
+    /// assert_eq!(golden.first_difference(&golden), None);
+    ///
+    /// // At the largest time
+    /// let (mut golden, mut dut) = (Signal::from_var(&var), Signal::from_var(&var));
+    /// for (signal, last) in [(&mut golden, Value::V1), (&mut dut, Value::V0)] {
+    ///     signal.add_event(0, ValueType::Value(Value::V0));
+    ///     signal.add_event(u64::MAX, ValueType::Value(last));
+    /// }
+    /// assert_eq!(golden.first_difference(&dut), Some(u64::MAX));
+    /// ```
+    pub fn differs_between(&self, other: &Signal, start: u64, end: u64) -> bool {
+        let width = self.width.max(other.width) as usize;
+        let differs_at = |t: u64| match (self.value_at(t), other.value_at(t)) {
+            (Some(a), Some(b)) => !same_value(&a, &b, width),
+            (a, b) => a.is_some() != b.is_some(),
+        };
+        differs_at(start)
+            || self
+                .events_between(start, end)
+                .chain(other.events_between(start, end))
+                .any(|(t, _)| differs_at(*t))
+    }
+
+    /// Time of the first difference between the values of the signal and of `other`, see
+    /// `differs_between`
+    pub fn first_difference(&self, other: &Signal) -> Option<u64> {
+        let mut times: Vec<u64> = self
+            .events
+            .iter()
+            .chain(other.events.iter())
+            .map(|(t, _)| *t)
+            .collect();
+        times.sort_unstable();
+        times.dedup();
+        times
+            .into_iter()
+            .find(|t| self.differs_between(other, *t, t.saturating_add(1)))
+    }
+
+    /// Time of the first event strictly after `t` that changes the value of the signal
+    /// Unlike `next_edge_after`, events repeating the previous value are skipped
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Reg, 4, 0u32.into(), "state".to_string(), None);
+    /// let mut state = Signal::from_var(&var);
+    /// for (time, value) in [(0, "0"), (10, "0000"), (20, "11"), (30, "0011"), (40, "0")] {
+    ///     state.add_event(time, ValueType::Vector(value.parse().unwrap()));
+    /// }
+    ///
+    /// assert_eq!(state.next_edge_after(0), Some(10));
+    /// assert_eq!(state.next_value_change(0), Some(20));
+    /// assert_eq!(state.next_value_change(20), Some(40));
+    /// assert_eq!(state.next_value_change(40), None);
+    /// assert_eq!(state.prev_value_change(40), Some(20));
+    /// assert_eq!(state.prev_value_change(20), Some(0));
+    /// assert_eq!(state.prev_value_change(0), None);
+    /// ```
+    pub fn next_value_change(&self, t: u64) -> Option<u64> {
+        let index = self.events.partition_point(|(time, _)| *time <= t);
+        (index..self.events.len())
+            .find(|i| self.is_value_change(*i))
+            .map(|i| self.events[i].0)
+    }
+
+    /// Time of the last event strictly before `t` that changes the value of the signal, see
+    /// `next_value_change`
+    pub fn prev_value_change(&self, t: u64) -> Option<u64> {
+        let index = self.events.partition_point(|(time, _)| *time < t);
+        (0..index)
+            .rev()
+            .find(|i| self.is_value_change(*i))
+            .map(|i| self.events[i].0)
+    }
+
+    /// Period of a single-bit signal toggling regularly, i.e. the time between its rising edges
+    /// when there are at least three of them, all the same time apart
+    /// The values before the first rising edge, e.g. `x` at reset, are ignored
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::Value;
+    ///
+    /// let toggle = |times: &[u64]| {
+    ///     let events = times.iter().enumerate().map(|(i, time)| {
+    ///         let value = if i % 2 == 0 { Value::V0 } else { Value::V1 };
+    ///         (*time, ValueType::Value(value))
+    ///     });
+    ///     Signal::with_events("clk", 0u32.into(), events.collect())
+    /// };
+    /// assert_eq!(toggle(&[0, 5, 10, 15, 20, 25]).detect_clock_period(), Some(10));
+    /// // Irregular
+    /// assert_eq!(toggle(&[0, 5, 10, 17, 20, 25]).detect_clock_period(), None);
+    /// // Too few edges
+    /// assert_eq!(toggle(&[0, 5, 10, 15]).detect_clock_period(), None);
+    /// ```
+    pub fn detect_clock_period(&self) -> Option<u64> {
+        if self.width != 1 {
+            return None;
+        }
+        let mut rising = self
+            .events
+            .windows(2)
+            .filter(|x| {
+                x[1].1 == ValueType::Value(Value::V1) && x[0].1 != ValueType::Value(Value::V1)
+            })
+            .map(|x| x[1].0);
+        let first = rising.next()?;
+        let second = rising.next()?;
+        let period = second - first;
+        let mut last = second;
+        let mut periods = 1;
+        for time in rising {
+            if time - last != period {
+                return None;
+            }
+            last = time;
+            periods += 1;
+        }
+        (periods >= 2 && period > 0).then_some(period)
+    }
+
+    /// Times of the events with an `x` or `z` value, in any bit of a vector
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    ///
+    /// let events = ["xxxx", "0101", "01z1", "0000", "x"]
+    ///     .iter()
+    ///     .enumerate()
+    ///     .map(|(i, value)| (i as u64 * 10, ValueType::Vector(value.parse().unwrap())))
+    ///     .collect();
+    /// let bus = Signal::with_events("bus", 0u32.into(), events);
+    /// assert_eq!(bus.unknown_times(), vec![0, 20, 40]);
+    /// ```
+    pub fn unknown_times(&self) -> Vec<u64> {
+        self.events
+            .iter()
+            .filter(|(_, value)| match value {
+                ValueType::Value(value) => matches!(value, Value::X | Value::Z),
+                ValueType::Vector(vector) => vector_contain_x_or_z(vector),
+            })
+            .map(|(time, _)| *time)
+            .collect()
+    }
+
+    /// Time of the change of value nearest to `t`, the earlier one on a tie, with the time since
+    /// the change before it, e.g. the half period of a clock, see `next_value_change`
+    ///
+    /// ```
+    /// use rata_wave::signal::{Signal, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// for (time, value) in [(0, Value::V0), (10, Value::V1), (20, Value::V0), (30, Value::V1)] {
+    ///     clk.add_event(time, ValueType::Value(value));
+    /// }
+    ///
+    /// assert_eq!(clk.nearest_value_change(2), Some((0, None)));
+    /// assert_eq!(clk.nearest_value_change(5), Some((0, None)));
+    /// assert_eq!(clk.nearest_value_change(14), Some((10, Some(10))));
+    /// assert_eq!(clk.nearest_value_change(16), Some((20, Some(10))));
+    /// assert_eq!(clk.nearest_value_change(25), Some((20, Some(10))));
+    /// assert_eq!(clk.nearest_value_change(100), Some((30, Some(10))));
+    /// ```
+    pub fn nearest_value_change(&self, t: u64) -> Option<(u64, Option<u64>)> {
+        let before = self.prev_value_change(t.saturating_add(1));
+        let after = self.next_value_change(t);
+        let nearest = match (before, after) {
+            (Some(before), Some(after)) if after - t < t - before => after,
+            (Some(before), _) => before,
+            (None, after) => after?,
+        };
+        let period = self.prev_value_change(nearest).map(|x| nearest - x);
+        Some((nearest, period))
+    }
+}
+
+/// Whether two values are the same, vectors are compared over `width` bits, see `extend_vector`
+fn same_value(a: &ValueType, b: &ValueType, width: usize) -> bool {
+    match (a, b) {
+        (ValueType::Vector(a), ValueType::Vector(b)) => {
+            extend_vector(a, width) == extend_vector(b, width)
+        }
+        (a, b) => a == b,
+    }
 }
 
 impl Display for Signal {
@@ -112,8 +856,43 @@ impl Display for Signal {
 }
 
 impl Signal {
+    /// Bit range of the signal as declared, e.g. `[7:0]` or `[0:7]`
+    /// Without a declared index, a vector is shown as `[width-1:0]` and a single bit has no range
+    ///
+    /// ```
+    /// use rata_wave::signal::Signal;
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 8, 0u32.into(), "data".to_string(), None);
+    /// assert_eq!(Signal::from_var(&var).output_range(), "[7:0]");
+    /// let index = Some("[0:7]".parse().unwrap());
+    /// let var = Var::new(VarType::Wire, 8, 0u32.into(), "data".to_string(), index);
+    /// assert_eq!(Signal::from_var(&var).output_range(), "[0:7]");
+    /// let index = Some("[3]".parse().unwrap());
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "data".to_string(), index);
+    /// assert_eq!(Signal::from_var(&var).output_range(), "[3]");
+    /// ```
+    pub fn output_range(&self) -> String {
+        match (self.index, self.width) {
+            (Some(index), _) => index.to_string(),
+            (None, 0 | 1) => String::new(),
+            (None, width) => format!("[{}:0]", width - 1),
+        }
+    }
+
+    /// Name of the signal with its range and code
+    ///
+    /// ```
+    /// use rata_wave::signal::Signal;
+    /// use vcd::{Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 8, 0u32.into(), "data".to_string(), None);
+    /// assert_eq!(Signal::from_var(&var).output_name(), "data[7:0](!)");
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// assert_eq!(Signal::from_var(&var).output_name(), "clk(!)");
+    /// ```
     pub fn output_name(&self) -> String {
-        format!("{}({})", self.name, self.code)
+        format!("{}{}({})", self.name, self.output_range(), self.code)
     }
     pub fn output_path(&self) -> String {
         let mut path =
@@ -121,7 +900,7 @@ impl Signal {
         if path.len() != 0 {
             path = path + ":"
         }
-        format!("{}{}({})", path, self.name, self.code)
+        format!("{}{}", path, self.output_name())
     }
     pub fn output_events(&self) -> String {
         format!("{:?}", self.events)
@@ -145,72 +924,214 @@ impl Signal {
     }
 
     /// Output a vector containing `DisplayEvents` in each time in the given time range
+    /// Column `i` covers `[time_start + i * time_step, time_start + (i + 1) * time_step)`, so an
+    /// event at time T is in the column whose range contains T
     /// - `time_start` - the start time
     /// - `time_step` - the minimal time step
     /// - `arr_size` - the size of the final array
+    ///
+    /// ```
+    /// use rata_wave::signal::{DisplayEvent, Signal, ValueDisplayEvent, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// for i in 0..1_000_000u64 {
+    ///     let value = if i % 2 == 0 { Value::V0 } else { Value::V1 };
+    ///     clk.add_event(i * 10, ValueType::Value(value));
+    /// }
+    ///
+    /// let arr = clk.events_arr_in_range(5_000_000, 10, 2);
+    /// assert!(matches!(arr[0], DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(Value::V0))));
+    /// assert!(matches!(arr[1], DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(Value::V1))));
+    ///
+    /// let arr = clk.events_arr_in_range(5_000_000, 5, 2);
+    /// assert!(matches!(arr[0], DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(Value::V0))));
+    /// assert!(matches!(arr[1], DisplayEvent::Value(ValueDisplayEvent::Stay(Value::V0))));
+    /// ```
+    ///
+    /// Events exactly on the boundaries of the columns:
+    ///
+    /// ```
+    /// use rata_wave::signal::{DisplayEvent, Signal, ValueDisplayEvent, ValueType};
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "clk".to_string(), None);
+    /// let mut clk = Signal::from_var(&var);
+    /// clk.add_event(100, ValueType::Value(Value::V1));
+    /// clk.add_event(110, ValueType::Value(Value::V0));
+    /// clk.add_event(130, ValueType::Value(Value::V1));
+    ///
+    /// use ValueDisplayEvent::*;
+    /// let arr = clk.events_arr_in_range(100, 10, 3);
+    /// // At `time_start`, the first event of the signal is an edge
+    /// assert!(matches!(arr[0], DisplayEvent::Value(ChangeEvent(Value::V1))));
+    /// // At `time_start + time_step`
+    /// assert!(matches!(arr[1], DisplayEvent::Value(ChangeEvent(Value::V0))));
+    /// assert!(matches!(arr[2], DisplayEvent::Value(Stay(Value::V0))));
+    ///
+    /// // An event at the end of the range is left out
+    /// let arr = clk.events_arr_in_range(110, 10, 2);
+    /// assert!(matches!(arr[0], DisplayEvent::Value(ChangeEvent(Value::V0))));
+    /// assert!(matches!(arr[1], DisplayEvent::Value(Stay(Value::V0))));
+    ///
+    /// // Columns not aligned with the events
+    /// let arr = clk.events_arr_in_range(101, 10, 3);
+    /// assert!(matches!(arr[0], DisplayEvent::Value(ChangeEvent(Value::V0))));
+    /// assert!(matches!(arr[1], DisplayEvent::Value(Stay(Value::V0))));
+    /// assert!(matches!(arr[2], DisplayEvent::Value(ChangeEvent(Value::V1))));
+    /// ```
+    ///
+    /// Events at the same time (delta cycles) make a column of several events showing the
+    /// settled value, which the next columns hold:
+    ///
+    /// ```
+    /// use rata_wave::signal::{DisplayEvent, Signal, ValueDisplayEvent, ValueType};
+    /// use vcd::Value;
+    ///
+    /// let events = [(0, Value::V0), (20, Value::V1), (20, Value::X), (20, Value::V0)]
+    ///     .map(|(time, value)| (time, ValueType::Value(value)));
+    /// let req = Signal::with_events("req", 0u32.into(), events.into());
+    ///
+    /// use ValueDisplayEvent::*;
+    /// let arr = req.events_arr_in_range(0, 10, 4);
+    /// assert!(matches!(arr[2], DisplayEvent::Value(MultipleEvent(Value::V0))));
+    /// assert!(matches!(arr[3], DisplayEvent::Value(Stay(Value::V0))));
+    /// assert_eq!(req.value_at(20), Some(ValueType::Value(Value::V0)));
+    /// ```
+    ///
+    /// Columns before the first event are undefined:
+    ///
+    /// ```
+    /// use rata_wave::signal::{DisplayEvent, Signal, ValueDisplayEvent, ValueType};
+    /// use rata_wave::signal::VectorDisplayEvent;
+    /// use vcd::{Value, Var, VarType};
+    ///
+    /// let var = Var::new(VarType::Wire, 1, 0u32.into(), "en".to_string(), None);
+    /// let mut en = Signal::from_var(&var);
+    /// en.add_event(50, ValueType::Value(Value::V1));
+    ///
+    /// let arr = en.events_arr_in_range(0, 10, 7);
+    /// for column in &arr[..5] {
+    ///     assert!(matches!(column, DisplayEvent::Value(ValueDisplayEvent::Stay(Value::X))));
+    /// }
+    /// assert!(matches!(arr[5], DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(Value::V1))));
+    /// assert!(matches!(arr[6], DisplayEvent::Value(ValueDisplayEvent::Stay(Value::V1))));
+    ///
+    /// let var = Var::new(VarType::Wire, 2, 1u32.into(), "bus".to_string(), None);
+    /// let mut bus = Signal::from_var(&var);
+    /// bus.add_event(50, ValueType::Vector("10".parse().unwrap()));
+    /// let arr = bus.events_arr_in_range(0, 10, 6);
+    /// let DisplayEvent::Vector(VectorDisplayEvent::Stay(vector)) = &arr[0] else { panic!() };
+    /// assert_eq!(vector.to_string(), "xx");
+    /// assert!(matches!(arr[5], DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(_))));
+    /// ```
+    ///
+    /// The same columns as a naive scan of all the events, over several windows:
+    ///
+    /// ```
+    /// use rata_wave::signal::{DisplayEvent, Signal, ValueDisplayEvent, ValueType};
+    /// use vcd::Value;
+    ///
+    /// // Pseudo-random events, with delta cycles and repeated values
+    /// let mut seed = 1u64;
+    /// let mut random = |n| {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     (seed >> 33) % n
+    /// };
+    /// let values = [Value::V0, Value::V1, Value::X, Value::Z];
+    /// let mut time = 0;
+    /// let mut events = Vec::new();
+    /// for _ in 0..2000 {
+    ///     time += [0, 0, 1, 5, 10][random(5) as usize];
+    ///     events.push((time, ValueType::Value(values[random(4) as usize])));
+    /// }
+    /// let sig = Signal::with_events("sig", 0u32.into(), events.clone());
+    ///
+    /// use ValueDisplayEvent::*;
+    /// let naive = |time_start: u64, time_step: u64, arr_size: u64| -> Vec<(&str, Value)> {
+    ///     let value_of = |(_, value): &(u64, ValueType)| match value {
+    ///         ValueType::Value(value) => *value,
+    ///         ValueType::Vector(_) => unreachable!(),
+    ///     };
+    ///     (0..arr_size)
+    ///         .map(|i| {
+    ///             let (start, end) = (time_start + i * time_step, time_start + (i + 1) * time_step);
+    ///             let before = events.iter().rposition(|(time, _)| *time < start);
+    ///             let inside: Vec<usize> =
+    ///                 (0..events.len()).filter(|j| (start..end).contains(&events[*j].0)).collect();
+    ///             match inside[..] {
+    ///                 [] => ("stay", before.map_or(Value::X, |j| value_of(&events[j]))),
+    ///                 [j] if j > 0 && events[j - 1].1 == events[j].1 => {
+    ///                     ("stay", value_of(&events[j]))
+    ///                 }
+    ///                 [j] => ("change", value_of(&events[j])),
+    ///                 [.., j] => ("multiple", value_of(&events[j])),
+    ///             }
+    ///         })
+    ///         .collect()
+    /// };
+    ///
+    /// let end = events.last().unwrap().0;
+    /// // Windows before the first event, on and between the events, and past the last one
+    /// for time_start in [0, 1, 2, 10, 99, 100, 101, end / 2, end - 7, end, end + 3] {
+    ///     for (time_step, arr_size) in [(1, 50), (3, 40), (10, 30), (100, 20)] {
+    ///         let arr: Vec<(&str, Value)> = sig
+    ///             .events_arr_in_range(time_start, time_step, arr_size as usize)
+    ///             .into_iter()
+    ///             .map(|event| match event {
+    ///                 DisplayEvent::Value(Stay(value)) => ("stay", value),
+    ///                 DisplayEvent::Value(ChangeEvent(value)) => ("change", value),
+    ///                 DisplayEvent::Value(MultipleEvent(value)) => ("multiple", value),
+    ///                 DisplayEvent::Vector(_) => unreachable!(),
+    ///             })
+    ///             .collect();
+    ///         assert_eq!(arr, naive(time_start, time_step, arr_size), "{}", time_start);
+    ///     }
+    /// }
+    /// ```
     pub fn events_arr_in_range(
         &self,
         time_start: u64,
         time_step: u64,
         arr_size: usize,
     ) -> Vec<DisplayEvent> {
-        let mut start_index = 0;
-        let mut end_index = 0;
+        // `events` is sorted by time, so the first event in the range can be binary searched
+        let mut start_index = self.events.partition_point(|(time, _)| *time < time_start);
 
-        while self.events[start_index].0 < time_start {
-            start_index += 1;
-            if start_index >= self.events.len() {
-                break;
-            }
-        }
-
-        let mut last_event =
-            match self
-                .events
-                .get(if start_index == 0 { 0 } else { start_index - 1 })
-            {
-                Some(event) => match &event.1 {
-                    ValueType::Value(value) => {
-                        DisplayEvent::Value(ValueDisplayEvent::Stay(value.clone()))
-                    }
-                    ValueType::Vector(vector) => {
-                        DisplayEvent::Vector(VectorDisplayEvent::Stay(vector.clone()))
-                    }
-                },
-                None => DisplayEvent::Value(ValueDisplayEvent::Stay(Value::X)),
-            };
+        // Columns before the first event of the signal are undefined
+        let mut last_event = match start_index.checked_sub(1).map(|i| &self.events[i]) {
+            Some(event) => match &event.1 {
+                ValueType::Value(value) => {
+                    DisplayEvent::Value(ValueDisplayEvent::Stay(value.clone()))
+                }
+                ValueType::Vector(vector) => {
+                    DisplayEvent::Vector(VectorDisplayEvent::Stay(vector.clone()))
+                }
+            },
+            None => match self.events.first() {
+                Some((_, ValueType::Vector(_))) => DisplayEvent::Vector(VectorDisplayEvent::Stay(
+                    std::iter::repeat_n(Value::X, self.width as usize).collect(),
+                )),
+                _ => DisplayEvent::Value(ValueDisplayEvent::Stay(Value::X)),
+            },
+        };
 
         let mut event_arr = vec![last_event.clone(); arr_size];
 
         event_arr.iter_mut().enumerate().for_each(|(i, element)| {
             if start_index >= self.events.len() {
-                *element = match &last_event {
-                    DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(value)) => {
-                        DisplayEvent::Value(ValueDisplayEvent::Stay(value.clone()))
-                    }
-                    DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(vector)) => {
-                        DisplayEvent::Vector(VectorDisplayEvent::Stay(vector.clone()))
-                    }
-                    _ => last_event.clone(),
-                };
+                *element = last_event.to_stay();
                 return;
             }
 
-            let start_time = time_start + (i as u64) * time_step;
-            end_index = start_index;
+            let start_time = time_start.saturating_add((i as u64).saturating_mul(time_step));
+            let mut end_index = start_index;
 
-            let end_time = start_time + time_step;
+            let end_time = start_time.saturating_add(time_step);
 
             if self.events[start_index].0 >= end_time {
-                *element = match &last_event {
-                    DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(value)) => {
-                        DisplayEvent::Value(ValueDisplayEvent::Stay(value.clone()))
-                    }
-                    DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(vector)) => {
-                        DisplayEvent::Vector(VectorDisplayEvent::Stay(vector.clone()))
-                    }
-                    _ => last_event.clone(),
-                };
+                *element = last_event.to_stay();
                 return;
             }
 
@@ -222,10 +1143,12 @@ impl Signal {
             }
 
             if end_index - start_index == 1 {
-                let event_prev = last_event.clone();
+                // The first event of the signal is a change, even if `last_event` already shows
+                // its value
+                let unchanged = start_index > 0 && last_event == self.events[start_index].1;
                 *element = match self.events[start_index].1.clone() {
                     ValueType::Value(value) => {
-                        if event_prev == self.events[start_index].1 {
+                        if unchanged {
                             DisplayEvent::Value(ValueDisplayEvent::Stay(value))
                         } else {
                             DisplayEvent::Value(ValueDisplayEvent::ChangeEvent(value))
@@ -233,7 +1156,7 @@ impl Signal {
                     }
 
                     ValueType::Vector(vector) => {
-                        if event_prev == self.events[start_index].1 {
+                        if unchanged {
                             DisplayEvent::Vector(VectorDisplayEvent::Stay(vector))
                         } else {
                             DisplayEvent::Vector(VectorDisplayEvent::ChangeEvent(vector))
@@ -242,9 +1165,13 @@ impl Signal {
                 };
                 last_event = element.clone();
             } else if end_index - start_index > 1 {
-                *element = match self.events[start_index].1 {
-                    ValueType::Value(_) => DisplayEvent::Value(ValueDisplayEvent::MultipleEvent),
-                    ValueType::Vector(_) => DisplayEvent::Vector(VectorDisplayEvent::MultipleEvent),
+                *element = match self.events[end_index - 1].1.clone() {
+                    ValueType::Value(value) => {
+                        DisplayEvent::Value(ValueDisplayEvent::MultipleEvent(value))
+                    }
+                    ValueType::Vector(vector) => {
+                        DisplayEvent::Vector(VectorDisplayEvent::MultipleEvent(vector))
+                    }
                 };
                 last_event = element.clone();
             } else {
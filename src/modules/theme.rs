@@ -0,0 +1,87 @@
+use catppuccin::{ColorName, PALETTE};
+use ratatui::style::Color;
+
+/// Catppuccin flavors the theme can be built from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flavor {
+    Latte,
+    Frappe,
+    Macchiato,
+    Mocha,
+}
+
+impl Flavor {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flavor::Latte => "Latte",
+            Flavor::Frappe => "Frappe",
+            Flavor::Macchiato => "Macchiato",
+            Flavor::Mocha => "Mocha",
+        }
+    }
+
+    /// The next flavor, wrapping around after Mocha
+    pub fn next(&self) -> Flavor {
+        match self {
+            Flavor::Latte => Flavor::Frappe,
+            Flavor::Frappe => Flavor::Macchiato,
+            Flavor::Macchiato => Flavor::Mocha,
+            Flavor::Mocha => Flavor::Latte,
+        }
+    }
+
+    fn color(&self, name: ColorName) -> Color {
+        match self {
+            Flavor::Latte => (*PALETTE.latte.get_color(name)).into(),
+            Flavor::Frappe => (*PALETTE.frappe.get_color(name)).into(),
+            Flavor::Macchiato => (*PALETTE.macchiato.get_color(name)).into(),
+            Flavor::Mocha => (*PALETTE.mocha.get_color(name)).into(),
+        }
+    }
+}
+
+/// Colors used for rendering
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub flavor: Flavor,
+    /// Signals holding their value
+    pub stay: Color,
+    /// Edges of single-bit signals
+    pub edge: Color,
+    /// Value changes of vectors
+    pub changed: Color,
+    /// `x` and `z` values, and invalid input
+    pub error: Color,
+    /// Valid input
+    pub valid: Color,
+    pub text: Color,
+    /// Selected items
+    pub accent: Color,
+    /// Background of the focused row
+    pub focus: Color,
+    /// Grid lines at the ticks of the time ruler
+    pub grid: Color,
+}
+
+impl Theme {
+    pub fn new(flavor: Flavor) -> Theme {
+        Theme {
+            flavor,
+            stay: flavor.color(ColorName::Green),
+            edge: flavor.color(ColorName::Teal),
+            changed: flavor.color(ColorName::Teal),
+            error: flavor.color(ColorName::Red),
+            valid: flavor.color(ColorName::Green),
+            text: flavor.color(ColorName::Text),
+            accent: flavor.color(ColorName::Blue),
+            focus: flavor.color(ColorName::Surface0),
+            grid: flavor.color(ColorName::Surface1),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(Flavor::Mocha)
+    }
+}
@@ -1,10 +1,10 @@
-use std::{cmp::max, fmt::Display, ops::Add, str::FromStr};
+use std::{fmt::Display, iter::successors, ops::Add, str::FromStr};
 
 use vcd::TimescaleUnit;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Time {
-    // Stored in ps
+    // Stored in fs
     time: u64,
 }
 
@@ -14,16 +14,33 @@ pub struct ParseTimeError {
 }
 
 impl Display for Time {
+    /// Show the time in the largest unit it is at least one of
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use std::str::FromStr;
+    /// use vcd::TimescaleUnit::*;
+    ///
+    /// assert_eq!(Time::new(0, FS).to_string(), "0fs");
+    /// assert_eq!(Time::new(999, FS).to_string(), "999fs");
+    /// assert_eq!(Time::new(1500, PS).to_string(), "1.5ns");
+    ///
+    /// // Round-trips through `from_str` in every unit
+    /// for (time, unit, shown) in [
+    ///     (1, PS, "1ps"),
+    ///     (25, NS, "25ns"),
+    ///     (100, US, "100us"),
+    ///     (7, MS, "7ms"),
+    ///     (2, S, "2s"),
+    /// ] {
+    ///     let t = Time::new(time, unit);
+    ///     assert_eq!(t.to_string(), shown);
+    ///     assert_eq!(Time::from_str(shown), Ok(t));
+    /// }
+    /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut t: f64 = self.time as f64;
-        // let mut scale = TimescaleUnit::PS;
-        use TimescaleUnit::*;
-        let scales = [PS, NS, US, MS, S];
-        let scale = scales
-            .iter()
-            .rfind(|x| t >= (PS.divisor() / x.divisor()) as f64)
-            .unwrap_or(&PS);
-        t = t / (PS.divisor() / scale.divisor()) as f64;
+        let scale = self.unit();
+        let t = self.time as f64 / (TimescaleUnit::FS.divisor() / scale.divisor()) as f64;
         write!(f, "{}{}", t, scale)
     }
 }
@@ -31,15 +48,49 @@ impl Display for Time {
 impl Add<u64> for Time {
     type Output = Time;
 
+    /// Add a time in fs, saturating at the largest time
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit::*;
+    ///
+    /// assert_eq!(Time::new(1, NS) + 500_000, Time::new(1500, PS));
+    /// assert_eq!((Time::new(u64::MAX - 1, FS) + 10).time(), u64::MAX);
+    /// ```
     fn add(self, rhs: u64) -> Self::Output {
         Time {
-            time: self.time + rhs,
+            time: self.time.saturating_add(rhs),
         }
     }
 }
 
 impl FromStr for Time {
     type Err = ParseTimeError;
+
+    /// Parse a time with its unit, e.g. "100ns", "2.5 us" or "3fs"
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use std::str::FromStr;
+    /// use vcd::TimescaleUnit::*;
+    ///
+    /// assert_eq!(Time::from_str(" 2.5 us "), Ok(Time::new(2500, NS)));
+    /// assert_eq!(Time::from_str("0.001ps"), Ok(Time::new(1, FS)));
+    /// // Exact, where a float would round
+    /// assert_eq!(Time::from_str("1.005ns").unwrap().time(), 1_005_000);
+    /// assert_eq!(Time::from_str("9007199254740993fs").unwrap().time(), (1 << 53) + 1);
+    /// assert_eq!(Time::from_str("18446.744073709551615s").unwrap().time(), u64::MAX);
+    /// assert_eq!(Time::from_str("18447s").unwrap_err().message(), "Time is too large");
+    ///
+    /// // Times that are not an integer in fs
+    /// assert_eq!(Time::from_str("0.0001ps").unwrap_err().message(), "Time must be an integer in fs");
+    /// assert!(Time::from_str("1.5fs").is_err());
+    /// // Missing or unknown parts
+    /// assert_eq!(Time::from_str("").unwrap_err().message(), "Empty string");
+    /// assert_eq!(Time::from_str("100").unwrap_err().message(), "Split error");
+    /// assert_eq!(Time::from_str("ns").unwrap_err().message(), "Parse time error");
+    /// assert_eq!(Time::from_str("100xs").unwrap_err().message(), "Parse unit error");
+    /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
         if s.len() == 0 {
@@ -56,77 +107,249 @@ impl FromStr for Time {
 
         let (time, unit) = s.split_at(split_index);
 
-        let time = time.parse::<f64>().map_err(|_| ParseTimeError {
-            message: "Parse time error".to_string(),
-        })?;
-        let unit = TimescaleUnit::from_str(unit.trim()).map_err(|_| ParseTimeError {
-            message: "Parse unit error".to_string(),
-        })?;
-
-        if unit == TimescaleUnit::FS {
-            return Err(ParseTimeError {
-                message: "Not support FS time scale".to_string(),
-            });
+        let error = |message: &str| ParseTimeError {
+            message: message.to_string(),
+        };
+        // The integer and the fraction digits are scaled separately, as a float would round
+        // e.g. 1.005 * 1e6 to 1004999.9999999999
+        let (integer, fraction) = time.split_once('.').unwrap_or((time, ""));
+        if (integer.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(error("Parse time error"));
         }
+        let fraction = fraction.trim_end_matches('0');
+        let unit = TimescaleUnit::from_str(unit.trim()).map_err(|_| error("Parse unit error"))?;
 
-        let time = time * (TimescaleUnit::PS.divisor() / unit.divisor()) as f64;
-        if time.fract() != 0.0 {
-            return Err(ParseTimeError {
-                message: "Time must be an integer in ps".to_string(),
-            });
+        let factor = (TimescaleUnit::FS.divisor() / unit.divisor()) as u128;
+        // A fraction finer than 1fs has more digits than the zeros of `factor`
+        if fraction.len() > factor.ilog10() as usize {
+            return Err(error("Time must be an integer in fs"));
         }
-        let time = time.trunc() as u64;
+        let digits = |s: &str| match s {
+            "" => Some(0),
+            _ => s.parse::<u128>().ok(),
+        };
+        let too_large = || error("Time is too large");
+        let integer = digits(integer).ok_or_else(too_large)?;
+        let fraction =
+            digits(fraction).ok_or_else(too_large)? * (factor / 10u128.pow(fraction.len() as u32));
+        let time = integer
+            .checked_mul(factor)
+            .and_then(|x| x.checked_add(fraction))
+            .and_then(|x| u64::try_from(x).ok())
+            .ok_or_else(too_large)?;
 
         Ok(Time { time })
     }
 }
 
 impl Time {
+    /// A time of `time` in the given unit
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit::*;
+    ///
+    /// assert_eq!(Time::new(3, PS).time(), 3_000);
+    /// assert_eq!(Time::new(3, S).time(), 3_000_000_000_000_000);
+    /// assert_eq!(Time::new(1000, NS), Time::new(1, US));
+    /// // Saturates instead of overflowing
+    /// assert_eq!(Time::new(u64::MAX / 10, PS).time(), u64::MAX);
+    /// ```
     pub fn new(time: u64, unit: TimescaleUnit) -> Self {
-        let time_in_ps = time * TimescaleUnit::PS.divisor() / unit.divisor();
-        Time { time: time_in_ps }
+        let time_in_fs = time.saturating_mul(TimescaleUnit::FS.divisor() / unit.divisor());
+        Time { time: time_in_fs }
     }
 
+    /// Increase the time, saturating at the largest time
+    /// Callers clamp it to the end of the waveform, see `App::clamp_time_start`
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// let mut t = Time::new(u64::MAX - 5, TimescaleUnit::FS);
+    /// t.increase(10);
+    /// assert_eq!(t.time(), u64::MAX);
+    /// ```
     pub fn increase(&mut self, time_inc: u64) {
-        self.time += time_inc;
+        self.time = self.time.saturating_add(time_inc);
     }
 
+    /// Decrease the time, stopping at 0
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// let mut t = Time::new(5, TimescaleUnit::FS);
+    /// t.decrease(10);
+    /// assert_eq!(t.time(), 0);
+    /// ```
     pub fn decrease(&mut self, time_dec: u64) {
-        self.time = if self.time < time_dec {
-            0
-        } else {
-            self.time - time_dec
-        }
+        self.time = self.time.saturating_sub(time_dec);
     }
 
+    /// The time in fs
     pub fn time(&self) -> u64 {
         self.time
     }
 
+    /// The mantissa of the time in its display unit, snapped down to the 1-2-5 sequence
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// assert_eq!(Time::new(7, TimescaleUnit::FS).formulate(), 5);
+    /// assert_eq!(Time::new(12, TimescaleUnit::FS).formulate(), 10);
+    /// assert_eq!(Time::new(333, TimescaleUnit::FS).formulate(), 200);
+    /// assert_eq!(Time::new(1000000, TimescaleUnit::FS).formulate(), 1);
+    /// assert_eq!(Time::new(2500, TimescaleUnit::FS).formulate(), 2);
+    /// ```
     pub fn formulate(&self) -> u64 {
         let mut t = self.time;
         while t >= 1000 {
-            if t % 1000 != 0 {
-                panic!("self.time can not divides 1000!")
-            }
             t /= 1000;
         }
-        t
+        step_sequence().take_while(|&x| x <= t).last().unwrap_or(t)
     }
 
+    /// Decrease the time to the previous value of the 1-2-5 sequence, stopping at 1fs
+    /// A time not in the sequence snaps to the largest value below it
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// for (time, expected) in [(1, 1), (2, 1), (3, 2), (20, 10), (250, 200), (1000, 500)] {
+    ///     let mut t = Time::new(time, TimescaleUnit::FS);
+    ///     t.step_decrease();
+    ///     assert_eq!(t.time(), expected);
+    /// }
+    /// ```
     pub fn step_decrease(&mut self) {
-        self.time = match self.formulate() {
-            1 | 10 | 100 => max(1, self.time / 2),
-            5 | 50 | 500 => self.time / 5,
-            _ => panic!("Invalid time step: {}", self.time),
-        }
+        self.time = step_sequence()
+            .take_while(|&x| x < self.time)
+            .last()
+            .unwrap_or(1);
     }
+
+    /// Increase the time to the next value of the 1-2-5 sequence
+    /// A time not in the sequence snaps to the smallest value above it
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// for (time, expected) in [(1, 2), (2, 5), (3, 5), (20, 50), (250, 500), (500, 1000)] {
+    ///     let mut t = Time::new(time, TimescaleUnit::FS);
+    ///     t.step_increase();
+    ///     assert_eq!(t.time(), expected);
+    /// }
+    ///
+    /// // An arbitrary step, e.g. set with `:step 25ns`, goes back to the sequence
+    /// let mut t = Time::new(25, TimescaleUnit::NS);
+    /// t.step_increase();
+    /// assert_eq!(t, Time::new(50, TimescaleUnit::NS));
+    /// t = Time::new(25, TimescaleUnit::NS);
+    /// t.step_decrease();
+    /// assert_eq!(t, Time::new(20, TimescaleUnit::NS));
+    /// ```
     pub fn step_increase(&mut self) {
-        self.time = match self.formulate() {
-            1 | 10 | 100 => self.time * 5,
-            5 | 50 | 500 => self.time * 2,
-            _ => panic!("Invalid time step: {}", self.time),
+        if let Some(time) = step_sequence().find(|&x| x > self.time) {
+            self.time = time;
+        }
+    }
+
+    /// The smallest step of the 1-2-5 sequence showing `span` fs in `columns` columns
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// assert_eq!(Time::fit_step(1000, 100).time(), 10);
+    /// assert_eq!(Time::fit_step(1001, 100).time(), 20);
+    /// assert_eq!(Time::fit_step(0, 100).time(), 1);
+    /// assert_eq!(Time::fit_step(1000, 0), Time::fit_step(1000, 1));
+    /// assert_eq!(Time::fit_step(3, 1), Time::new(5, TimescaleUnit::FS));
+    /// ```
+    pub fn fit_step(span: u64, columns: usize) -> Time {
+        let columns = columns.max(1) as u64;
+        let time = step_sequence()
+            .find(|x| x.saturating_mul(columns) >= span)
+            .unwrap_or(u64::MAX);
+        Time { time }
+    }
+
+    /// The largest unit the time is at least one of, `FS` for 0
+    pub fn unit(&self) -> TimescaleUnit {
+        use TimescaleUnit::*;
+        [FS, PS, NS, US, MS, S]
+            .into_iter()
+            .rfind(|x| self.time >= FS.divisor() / x.divisor())
+            .unwrap_or(FS)
+    }
+
+    /// Format the time in the given unit, or in `Time::unit` if None, rounded to `precision`
+    /// digits after the decimal point
+    /// Unlike `Display`, the value is computed without floating point, and trailing zeros are
+    /// left out
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit::*;
+    ///
+    /// let t = Time::new(3333333, FS);
+    /// assert_eq!(t.to_string(), "3.333333ns");
+    /// assert_eq!(t.format(None, 2), "3.33ns");
+    /// assert_eq!(t.format(Some(PS), 1), "3333.3ps");
+    /// assert_eq!(t.format(Some(US), 3), "0.003us");
+    ///
+    /// // Rounded half up, without trailing zeros
+    /// assert_eq!(Time::new(1500, PS).format(None, 0), "2ns");
+    /// assert_eq!(Time::new(1500, PS).format(None, 3), "1.5ns");
+    /// assert_eq!(Time::new(0, FS).format(None, 3), "0fs");
+    /// ```
+    pub fn format(&self, unit: Option<TimescaleUnit>, precision: usize) -> String {
+        let unit = unit.unwrap_or_else(|| self.unit());
+        let unit_fs = (TimescaleUnit::FS.divisor() / unit.divisor()) as u128;
+        let scale = 10u128.saturating_pow(precision as u32);
+        let scaled = (self.time as u128 * scale + unit_fs / 2) / unit_fs;
+
+        let mut s = (scaled / scale).to_string();
+        let fraction = format!("{:0width$}", scaled % scale, width = precision);
+        let fraction = fraction.trim_end_matches('0');
+        if precision > 0 && !fraction.is_empty() {
+            s = format!("{}.{}", s, fraction);
         }
+        format!("{}{}", s, unit)
+    }
+
+    /// Frequency of a period of this time, with 3 decimals at most, e.g. `100MHz` for 10ns
+    /// Return None for a period of 0
+    ///
+    /// ```
+    /// use rata_wave::time::Time;
+    /// use vcd::TimescaleUnit::*;
+    ///
+    /// assert_eq!(Time::new(10, NS).frequency().as_deref(), Some("100MHz"));
+    /// assert_eq!(Time::new(3, NS).frequency().as_deref(), Some("333.333MHz"));
+    /// assert_eq!(Time::new(250, PS).frequency().as_deref(), Some("4GHz"));
+    /// assert_eq!(Time::new(2, S).frequency().as_deref(), Some("0.5Hz"));
+    /// assert_eq!(Time::new(0, NS).frequency(), None);
+    /// ```
+    pub fn frequency(&self) -> Option<String> {
+        if self.time == 0 {
+            return None;
+        }
+        let hz = TimescaleUnit::FS.divisor() as f64 / self.time as f64;
+        let (value, unit) = [(1e12, "THz"), (1e9, "GHz"), (1e6, "MHz"), (1e3, "kHz")]
+            .into_iter()
+            .find(|(scale, _)| hz >= *scale)
+            .map_or((hz, "Hz"), |(scale, unit)| (hz / scale, unit));
+        let value = format!("{:.3}", value);
+        let value = value.trim_end_matches('0').trim_end_matches('.');
+        Some(format!("{}{}", value, unit))
     }
 
     /// Check if the given string is a valid time
@@ -139,11 +362,12 @@ impl Time {
     /// assert!(Time::is_valid("100 ns").is_ok());
     /// assert!(Time::is_valid("0.5us").is_ok());
     /// assert!(Time::is_valid("100.001ns").is_ok());
-    /// // Since 1ps is the smallest time, if the time representation is not an integer in ps it
+    /// assert!(Time::is_valid("100fs").is_ok());
+    /// assert!(Time::is_valid("0.1ps").is_ok());
+    /// // Since 1fs is the smallest time, if the time representation is not an integer in fs it
     /// // will generate an error
-    /// assert!(Time::is_valid("1ps").is_ok());
-    /// assert!(Time::is_valid("0.1ps").is_err());
-    /// assert!(Time::is_valid("100.0001ns").is_err());
+    /// assert!(Time::is_valid("0.1fs").is_err());
+    /// assert!(Time::is_valid("100.0000001ns").is_err());
     /// ```
     pub fn is_valid(s: &str) -> Result<(), ParseTimeError> {
         match Time::from_str(s) {
@@ -153,6 +377,15 @@ impl Time {
     }
 }
 
+/// The 1-2-5 sequence of time steps, i.e. 1, 2, 5, 10, 20, 50, ...
+pub(crate) fn step_sequence() -> impl Iterator<Item = u64> {
+    successors(Some(1u64), |decade| decade.checked_mul(10)).flat_map(|decade| {
+        [1, 2, 5]
+            .into_iter()
+            .filter_map(move |m| decade.checked_mul(m))
+    })
+}
+
 impl ParseTimeError {
     pub fn message(&self) -> &str {
         self.message.as_str()
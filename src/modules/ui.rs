@@ -1,11 +1,310 @@
+use ratatui::symbols::border;
+
+use super::keymap::Action;
+
 pub const S_RISING_EDGE: [&str; 2] = ["┌", "┘"];
 pub const S_FALLING_EDGE: [&str; 2] = ["┐", "└"];
 pub const S_STAY_1: [&str; 2] = ["─", " "];
 pub const S_STAY_0: [&str; 2] = [" ", "─"];
 pub const S_STAY_X: [&str; 2] = ["x", "x"];
 pub const S_STAY_Z: [&str; 2] = ["z", "z"];
-pub const S_MULTIPLE: [&str; 2] = ["␩", "␩"];
+/// Pulses narrower than a column, from 0 and from 1, see `Signal::glitch_between`
+pub const S_GLITCH_0: [&str; 2] = ["╻", "┸"];
+pub const S_GLITCH_1: [&str; 2] = ["┰", "╹"];
 
-pub const M_CHANGE: [&str; 3] = ["┬", "│", "┴"];
-pub const M_MULTIPLE: [&str; 3] = ["␩", "␩", "␩"];
+/// A bus transition is drawn over two columns, the rails of the old value close in the column
+/// before the change and open again in the changing column, so that it reads as `─╲╱─`/`─╱╲─`
+pub const M_CHANGE: [&str; 3] = ["╱", "╳", "╲"];
+pub const M_CLOSE: [&str; 3] = ["╲", " ", "╱"];
 pub const M_STAY: [&str; 3] = ["─", " ", "─"];
+
+/// Shades of a column with several transitions, denser for more transitions
+/// See `utils::density_index`
+pub const D_MULTIPLE: [&str; 4] = ["░", "▒", "▓", "█"];
+
+/// Single-bit signals in the compact mode, a level in the upper or lower half of a cell and a
+/// full cell for the columns with transitions
+pub const C_HIGH: &str = "▀";
+pub const C_LOW: &str = "▄";
+pub const C_CHANGE: &str = "█";
+
+/// Arrows on the edges of a row whose signal changes before or after the view
+pub const O_BEFORE: &str = "‹";
+pub const O_AFTER: &str = "›";
+
+/// Blocks filling a cell from the bottom in eighths, used by the analog mode
+pub const A_BLOCKS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+
+/// The glyphs of the waveforms and of the rest of the UI, see the constants above for what each
+/// set of the waveforms draws
+/// - `grid` - grid lines drawn down from the ticks of the time ruler
+/// - `overflow` - cells of a vector value too long for its columns
+/// - `line` - divider rows
+/// - `bookmark` - marker of a bookmark above the ruler
+/// - `ellipsis` - replaces the middle of the names too long for their column
+/// - `collapsed`, `expanded` - markers of the modules in the signal picker
+/// - `border` - borders of the boxes
+#[derive(Debug, PartialEq)]
+pub struct Glyphs {
+    pub rising_edge: [&'static str; 2],
+    pub falling_edge: [&'static str; 2],
+    pub stay_1: [&'static str; 2],
+    pub stay_0: [&'static str; 2],
+    pub stay_x: [&'static str; 2],
+    pub stay_z: [&'static str; 2],
+    pub glitch_0: [&'static str; 2],
+    pub glitch_1: [&'static str; 2],
+    pub change: [&'static str; 3],
+    pub close: [&'static str; 3],
+    pub stay: [&'static str; 3],
+    pub multiple: [&'static str; 4],
+    pub high: &'static str,
+    pub low: &'static str,
+    pub changing: &'static str,
+    pub before: &'static str,
+    pub after: &'static str,
+    pub blocks: [&'static str; 9],
+    pub grid: &'static str,
+    pub overflow: &'static str,
+    pub line: &'static str,
+    pub differs: &'static str,
+    pub bookmark: char,
+    pub ellipsis: &'static str,
+    pub collapsed: &'static str,
+    pub expanded: &'static str,
+    pub border: border::Set,
+}
+
+/// Box drawing and block characters, the default
+pub const UNICODE_GLYPHS: Glyphs = Glyphs {
+    rising_edge: S_RISING_EDGE,
+    falling_edge: S_FALLING_EDGE,
+    stay_1: S_STAY_1,
+    stay_0: S_STAY_0,
+    stay_x: S_STAY_X,
+    stay_z: S_STAY_Z,
+    glitch_0: S_GLITCH_0,
+    glitch_1: S_GLITCH_1,
+    change: M_CHANGE,
+    close: M_CLOSE,
+    stay: M_STAY,
+    multiple: D_MULTIPLE,
+    high: C_HIGH,
+    low: C_LOW,
+    changing: C_CHANGE,
+    before: O_BEFORE,
+    after: O_AFTER,
+    blocks: A_BLOCKS,
+    grid: "┊",
+    overflow: "␩",
+    line: "─",
+    differs: "≠",
+    bookmark: '▼',
+    ellipsis: "…",
+    collapsed: "▸",
+    expanded: "▾",
+    border: border::PLAIN,
+};
+
+/// ASCII characters only, for the terminals and fonts without the box drawing characters
+/// A rising edge reads as `_/-` and a bus transition as `-X-`
+pub const ASCII_GLYPHS: Glyphs = Glyphs {
+    rising_edge: ["/", "/"],
+    falling_edge: ["\\", "\\"],
+    stay_1: ["-", " "],
+    stay_0: [" ", "_"],
+    stay_x: ["X", "X"],
+    stay_z: ["Z", "Z"],
+    glitch_0: ["|", "|"],
+    glitch_1: ["|", "|"],
+    change: ["/", "X", "\\"],
+    close: ["\\", " ", "/"],
+    stay: ["-", " ", "_"],
+    multiple: [".", ":", "|", "#"],
+    high: "-",
+    low: "_",
+    changing: "|",
+    before: "<",
+    after: ">",
+    blocks: [" ", "_", "_", "-", "-", "=", "=", "#", "#"],
+    grid: ":",
+    overflow: "~",
+    line: "-",
+    differs: "!",
+    bookmark: 'v',
+    ellipsis: "...",
+    collapsed: ">",
+    expanded: "v",
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+};
+
+/// Default key bindings of the actions of the normal mode with their descriptions, shown in the
+/// help overlay, see `keymap::KeyMap`
+pub const KEY_BINDINGS: &[(Action, &str, &str)] = &[
+    (Action::AddSignal, "a", "Add signals"),
+    (Action::Quit, "q", "Quit"),
+    (
+        Action::Clear,
+        "C",
+        "Remove all displayed signals and dividers, after a confirmation",
+    ),
+    (Action::ZoomIn, "=", "Zoom in around the cursor"),
+    (Action::ZoomOut, "-", "Zoom out around the cursor"),
+    (
+        Action::ZoomFit,
+        "0",
+        "Zoom to fit the whole waveform in the window",
+    ),
+    (Action::ScrollLeft, "h", "Scroll left by half a screen"),
+    (Action::ScrollRight, "l", "Scroll right by half a screen"),
+    (
+        Action::GotoTime,
+        "t",
+        "Go to a time, putting it at the left edge",
+    ),
+    (
+        Action::CenterTime,
+        "c",
+        "Go to a time, putting it in the middle",
+    ),
+    (Action::SetStep, "S", "Set the time of a column, e.g. 25ns"),
+    (Action::CursorLeft, "Left", "Move the cursor left"),
+    (Action::CursorRight, "Right", "Move the cursor right"),
+    (
+        Action::CopyValue,
+        "y",
+        "Copy the value of the focused signal at the cursor (needs the clipboard feature)",
+    ),
+    (Action::MarkerA, "1", "Put marker A at the cursor"),
+    (Action::MarkerB, "2", "Put marker B at the cursor"),
+    (
+        Action::SetOrigin,
+        "o",
+        "Count the times of the ruler from the cursor, or from 0 again when it is the origin",
+    ),
+    (Action::FocusNext, "j", "Focus the next signal"),
+    (Action::FocusPrev, "k", "Focus the previous signal"),
+    (
+        Action::NextEdge,
+        "n",
+        "Jump to the next edge of the focused signal, or the next match of :find",
+    ),
+    (
+        Action::PrevEdge,
+        "N",
+        "Jump to the previous edge of the focused signal, or the previous match of :find",
+    ),
+    (
+        Action::NextChange,
+        "]",
+        "Jump to the next change of any displayed signal",
+    ),
+    (
+        Action::PrevChange,
+        "[",
+        "Jump to the previous change of any displayed signal",
+    ),
+    (Action::MoveDown, "J", "Move the focused row down"),
+    (Action::MoveUp, "K", "Move the focused row up"),
+    (
+        Action::InsertDivider,
+        "d",
+        "Insert a divider below the focused row",
+    ),
+    (
+        Action::Rename,
+        "R",
+        "Rename the focused signal, an empty name removes the alias",
+    ),
+    (
+        Action::ToggleCompact,
+        "v",
+        "Toggle the compact mode, one row per signal",
+    ),
+    (
+        Action::ToggleDiff,
+        "D",
+        "Compare signals with the same signals of another file, highlighting where they differ",
+    ),
+    (Action::Grow, ">", "Make the focused signal taller"),
+    (Action::Shrink, "<", "Make the focused signal shorter"),
+    (
+        Action::ToggleAnalog,
+        "A",
+        "Toggle the analog mode of the focused vector",
+    ),
+    (
+        Action::CycleFormat,
+        "f",
+        "Cycle the format of the focused vector: bin, dec, sdec, hex and analog",
+    ),
+    (
+        Action::ToggleGrid,
+        "g",
+        "Toggle the grid lines at the ticks of the time ruler",
+    ),
+    (
+        Action::NextTheme,
+        "T",
+        "Switch the color theme between the Catppuccin flavors",
+    ),
+    (
+        Action::SaveSession,
+        "w",
+        "Save the session next to the waveform file",
+    ),
+    (
+        Action::ExportSvg,
+        "s",
+        "Export the view to ratawave.svg next to the waveform file",
+    ),
+    (
+        Action::ExportCsv,
+        "e",
+        "Export the displayed signals over the view, or between the markers, to a CSV file",
+    ),
+    (
+        Action::Command,
+        ":",
+        "Enter a command: goto <time>, add <path>, add /<regex>/, radix <bin|dec|sdec|hex|analog>, remove, step <time>, map <value=label,...|file>, find <value|0b<bits>>",
+    ),
+    (
+        Action::AddBookmark,
+        "m",
+        "Add a bookmark with a label at the cursor",
+    ),
+    (
+        Action::Bookmarks,
+        "'",
+        "List the bookmarks, Enter jumps to one and d deletes it",
+    ),
+    (
+        Action::Undo,
+        "u",
+        "Undo the last change of the view or the displayed signals",
+    ),
+    (Action::Redo, "Ctrl-r", "Redo the last undone change"),
+    (
+        Action::Reload,
+        "r",
+        "Reload the waveform files, keeping the view and the displayed signals",
+    ),
+    (
+        Action::Info,
+        "i",
+        "Show the metadata of the waveform files, e.g. their $version",
+    ),
+    (Action::Help, "?", "Show this help"),
+];
+
+/// Mouse bindings, shown in the help overlay after the key bindings
+pub const MOUSE_HELP: &str = "Click a signal to focus it, click a waveform to move the cursor, drag across it to zoom into a time range, right-click it to copy its value, scroll the wheel to zoom and hold Shift to scroll the view";
@@ -0,0 +1,266 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use vcd::TimescaleUnit;
+
+#[cfg(feature = "fst")]
+use crate::utils::restrict_to_scope;
+use crate::{
+    error::WaveError,
+    module::Module,
+    signal::{Signal, ValueType},
+    time::Time,
+    utils::{
+        FileInfo, STDIN_PATH, parse_files_in_scope,
+        windowed::{CHECKPOINT_BYTES, WindowedStore, index_file},
+    },
+};
+
+/// A parsed waveform file, usable without the TUI
+pub struct Waveform {
+    root: Rc<RefCell<Module>>,
+    timescale: TimescaleUnit,
+    warnings: Vec<String>,
+    info: FileInfo,
+    // Index of the file when only the events of a time window are loaded
+    windowed: Option<WindowedStore>,
+}
+
+/// Options of loading a waveform file
+/// - `windowed` - only load the events around the visible window, see `Waveform::load_window`
+///   Searching edges and sampling markers then only see the loaded events
+///   The standard input cannot be read again, so it is always fully loaded
+/// - `scope` - path of the only module to load with its submodules, e.g. `top.cpu.core0`, see
+///   `load_scope`
+#[derive(Default, Clone)]
+pub struct LoadOptions {
+    pub windowed: bool,
+    pub scope: Option<String>,
+}
+
+/// Load a waveform file
+/// FST files (`.fst`) are supported when the `fst` feature is enabled, other files are parsed
+/// as VCD, and `-` reads a VCD file from the standard input
+///
+/// ```
+/// use rata_wave::{signal::ValueType, time::Time};
+/// use std::str::FromStr;
+/// use vcd::Value;
+///
+/// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+/// let clk = waveform.signal_by_path("test_tb->i1:clk").unwrap();
+///
+/// let value = waveform.value_at(&clk.borrow(), &Time::from_str("5ns").unwrap());
+/// assert_eq!(value, Some(ValueType::Value(Value::V0)));
+/// let value = waveform.value_at(&clk.borrow(), &Time::from_str("15ns").unwrap());
+/// assert_eq!(value, Some(ValueType::Value(Value::V1)));
+/// ```
+pub fn load(path: &str) -> Result<Waveform, WaveError> {
+    load_with_progress(path, &LoadOptions::default(), &mut |_, _| {})
+}
+
+/// Load only the module given by its path and its submodules, reducing the memory used by large
+/// files
+/// The modules above the scope are kept without their other submodules and signals, and an
+/// unknown scope is an error
+/// FST files are fully parsed before the other modules are dropped
+///
+/// ```
+/// let waveform = rata_wave::load_scope("assets/verilog/test_1.vcd", "test_tb.i1").unwrap();
+/// let clk = waveform.signal_by_path("test_tb->i1:clk").unwrap();
+/// assert!(!clk.borrow().events.is_empty());
+/// assert!(waveform.signal_by_path("test_tb:clk").is_none());
+///
+/// let error = rata_wave::load_scope("assets/verilog/test_1.vcd", "test_tb.cpu").err().unwrap();
+/// assert!(matches!(error, rata_wave::error::WaveError::UnknownScope(_)));
+/// assert_eq!(error.to_string(), "No scope test_tb.cpu");
+/// ```
+pub fn load_scope(path: &str, scope: &str) -> Result<Waveform, WaveError> {
+    let options = LoadOptions {
+        scope: Some(scope.to_string()),
+        ..Default::default()
+    };
+    load_with_progress(path, &options, &mut |_, _| {})
+}
+
+/// Same as `load`, calling `progress` with the bytes read so far and the size of the file while
+/// parsing
+/// Windowed loading is only supported for VCD files, other files are fully loaded
+///
+/// ```
+/// use rata_wave::waveform::{LoadOptions, load_with_progress};
+///
+/// let options = LoadOptions {
+///     windowed: true,
+///     ..Default::default()
+/// };
+/// let mut waveform =
+///     load_with_progress("assets/verilog/test_1.vcd", &options, &mut |_, _| {}).unwrap();
+/// let clk = waveform.signal_by_path("test_tb:clk").unwrap();
+/// // Nothing is loaded until a window is asked for
+/// assert!(clk.borrow().events.is_empty());
+///
+/// let full = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+/// assert_eq!(waveform.max_time(), full.max_time());
+///
+/// // The window, and one more window on each side, are loaded
+/// waveform.load_window(100_000_000, 200_000_000).unwrap();
+/// let full_clk = full.signal_by_path("test_tb:clk").unwrap();
+/// for time in (0..300_000_000).step_by(5_000_000) {
+///     assert_eq!(clk.borrow().value_at(time), full_clk.borrow().value_at(time));
+/// }
+///
+/// // A truncated file keeps the events before the error
+/// let mut waveform =
+///     load_with_progress("assets/verilog/truncated.vcd", &options, &mut |_, _| {}).unwrap();
+/// assert!(waveform.warnings()[0].starts_with("Stopped reading at 20ns"));
+/// waveform.load_window(0, 30_000_000).unwrap();
+/// let clk = waveform.signal_by_path("test_tb:clk").unwrap();
+/// assert_eq!(clk.borrow().events.len(), 3);
+///
+/// // As does a file whose times in fs overflow
+/// let mut waveform =
+///     load_with_progress("assets/verilog/overflow.vcd", &options, &mut |_, _| {}).unwrap();
+/// assert!(waveform.warnings()[0].starts_with("Stopped reading at 10s: Unsupported: timestamp"));
+/// waveform.load_window(0, u64::MAX).unwrap();
+/// let clk = waveform.signal_by_path("test_tb:clk").unwrap();
+/// assert_eq!(clk.borrow().events.len(), 2);
+/// ```
+pub fn load_with_progress(
+    path: &str,
+    options: &LoadOptions,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<Waveform, WaveError> {
+    let mut windowed = None;
+    let scope = options.scope.as_deref();
+    let (root, timescale, warnings, info) =
+        match Path::new(path).extension().and_then(|x| x.to_str()) {
+            #[cfg(feature = "fst")]
+            Some("fst") => {
+                let parsed = crate::utils::fst::parse_fst_file(path.to_string())?;
+                if let Some(scope) = scope {
+                    restrict_to_scope(&parsed.0, scope)?;
+                }
+                parsed
+            }
+            #[cfg(not(feature = "fst"))]
+            Some("fst") => {
+                return Err(WaveError::UnsupportedFeature(
+                    "FST files, which require the `fst` feature".to_string(),
+                ));
+            }
+            _ if options.windowed && path != STDIN_PATH => {
+                let (parsed, store) =
+                    index_file(path.to_string(), scope, CHECKPOINT_BYTES, progress)?;
+                windowed = Some(store);
+                parsed
+            }
+            _ => parse_files_in_scope(path.to_string(), scope, progress)?,
+        };
+    Ok(Waveform {
+        root,
+        timescale,
+        warnings,
+        info,
+        windowed,
+    })
+}
+
+impl Waveform {
+    /// A fully loaded waveform of the given module tree, e.g. one built by `ModuleBuilder`
+    ///
+    /// ```
+    /// use rata_wave::{Waveform, module::ModuleBuilder, signal::{Signal, ValueType}};
+    /// use vcd::{TimescaleUnit, Value};
+    ///
+    /// let events = vec![(0, ValueType::Value(Value::V0)), (10, ValueType::Value(Value::V1))];
+    /// let clk = Signal::with_events("clk", 0u32.into(), events);
+    /// let root = ModuleBuilder::root()
+    ///     .submodule(ModuleBuilder::new("top").signal(clk))
+    ///     .build();
+    /// let waveform = Waveform::new(root, TimescaleUnit::NS);
+    /// assert_eq!(waveform.max_time(), 10);
+    /// assert_eq!(waveform.signal_by_path("top:clk").unwrap().borrow().width, 1);
+    /// ```
+    pub fn new(root: Rc<RefCell<Module>>, timescale: TimescaleUnit) -> Self {
+        Waveform {
+            root,
+            timescale,
+            warnings: vec![],
+            info: FileInfo::default(),
+            windowed: None,
+        }
+    }
+
+    pub fn root(&self) -> Rc<RefCell<Module>> {
+        Rc::clone(&self.root)
+    }
+
+    pub fn timescale(&self) -> TimescaleUnit {
+        self.timescale
+    }
+
+    /// Time of the last event in fs
+    pub fn max_time(&self) -> u64 {
+        match &self.windowed {
+            Some(store) => store.max_time(),
+            None => self.root.borrow().max_time(),
+        }
+    }
+
+    /// Load the events between `time_start` and `time_end` (in fs) when the file is loaded
+    /// windowed, see `WindowedStore::load_window`
+    /// Do nothing when the file is fully loaded
+    pub fn load_window(&mut self, time_start: u64, time_end: u64) -> Result<(), WaveError> {
+        match &mut self.windowed {
+            Some(store) => store.load_window(time_start, time_end),
+            None => Ok(()),
+        }
+    }
+
+    /// Problems met while parsing that did not prevent loading the file
+    ///
+    /// ```
+    /// use rata_wave::error::WaveError;
+    /// use vcd::TimescaleUnit;
+    ///
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// assert!(waveform.warnings().is_empty());
+    ///
+    /// // A file without `$timescale` falls back to 1ns
+    /// let waveform = rata_wave::load("assets/verilog/no_timescale.vcd").unwrap();
+    /// assert_eq!(waveform.timescale(), TimescaleUnit::NS);
+    /// assert_eq!(waveform.warnings(), [WaveError::NoTimescale.to_string()]);
+    /// assert_eq!(waveform.warnings()[0], "No timescale in the file, assuming 1ns");
+    /// let clk = waveform.signal_by_path("test_tb:clk").unwrap();
+    /// assert_eq!(clk.borrow().events[1].0, 10_000_000);
+    /// ```
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Metadata of the header of the file, e.g. its `$version`
+    ///
+    /// ```
+    /// let waveform = rata_wave::load("assets/verilog/test_1.vcd").unwrap();
+    /// assert_eq!(waveform.info().version.as_deref(), Some("Icarus Verilog"));
+    /// ```
+    pub fn info(&self) -> &FileInfo {
+        &self.info
+    }
+
+    pub fn signals(&self) -> Vec<Rc<RefCell<Signal>>> {
+        self.root.borrow().get_signals()
+    }
+
+    /// Find a signal by the path shown in the UI (see `Signal::output_path`)
+    /// The trailing `(code)` can be omitted, e.g. "test_tb->i1:clk", see `Module::find_by_path`
+    pub fn signal_by_path(&self, path: &str) -> Option<Rc<RefCell<Signal>>> {
+        self.root.borrow().find_by_path(path)
+    }
+
+    /// Value of the signal at the given time
+    /// Return None if the time is before the first event of the signal
+    pub fn value_at(&self, signal: &Signal, time: &Time) -> Option<ValueType> {
+        signal.value_at(time.time())
+    }
+}
@@ -0,0 +1,8 @@
+use std::io;
+
+/// Put `text` into the system clipboard
+pub fn copy(text: &str) -> io::Result<()> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(io::Error::other)
+}
@@ -0,0 +1,186 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    rc::Rc,
+    str,
+};
+
+use fst_reader::{
+    FstFilter, FstHierarchyEntry, FstReader, FstScopeType, FstSignalValue, FstVarType,
+};
+use vcd::{IdCode, ScopeType, TimescaleUnit, Value, Var, VarType, Vector};
+
+use crate::{
+    error::WaveError,
+    module::Module,
+    signal::{Signal, ValueType},
+};
+
+fn fst_error(e: fst_reader::ReaderError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// Convert a FST value string to a `ValueType`
+/// States VCD can not represent (e.g. `u`, `w`, `-`) are mapped to `x`
+fn value_from_bytes(bytes: &[u8]) -> ValueType {
+    let parse = |c: u8| {
+        str::from_utf8(&[c])
+            .ok()
+            .and_then(|x| x.parse::<Value>().ok())
+            .unwrap_or(Value::X)
+    };
+    match bytes {
+        [c] => ValueType::Value(parse(*c)),
+        _ => ValueType::Vector(bytes.iter().map(|c| parse(*c)).collect::<Vector>()),
+    }
+}
+
+/// Convert a FST variable type to the VCD one, SystemVerilog integer types are `integer` and
+/// types VCD does not have are `wire`
+fn var_type_of(tpe: FstVarType) -> VarType {
+    use FstVarType::*;
+    match tpe {
+        Event => VarType::Event,
+        Integer | Int | ShortInt | LongInt | Byte => VarType::Integer,
+        Parameter | RealParameter => VarType::Parameter,
+        Real | ShortReal | RealTime => VarType::Real,
+        Reg | Bit | Logic => VarType::Reg,
+        Supply0 => VarType::Supply0,
+        Supply1 => VarType::Supply1,
+        Time => VarType::Time,
+        Tri => VarType::Tri,
+        TriAnd => VarType::TriAnd,
+        TriOr => VarType::TriOr,
+        TriReg => VarType::TriReg,
+        Tri0 => VarType::Tri0,
+        Tri1 => VarType::Tri1,
+        Wand => VarType::WAnd,
+        Wor => VarType::WOr,
+        GenericString => VarType::String,
+        Wire | Port | SparseArray | Enum => VarType::Wire,
+    }
+}
+
+/// Parse a FST file into the same module tree as `parse_files`
+pub fn parse_fst_file(file_name: String) -> Result<super::ParsedFile, WaveError> {
+    let root = Rc::new(RefCell::new(Module {
+        name: String::from("Root"),
+        scope_type: ScopeType::Module,
+        depth: 1,
+        signals: vec![],
+        submodules: vec![],
+        parent: None,
+    }));
+
+    let mut reader = FstReader::open(BufReader::new(File::open(file_name)?)).map_err(fst_error)?;
+
+    use TimescaleUnit::*;
+    let time_scale = match reader.get_header().timescale_exponent {
+        -15 => FS,
+        -12 => PS,
+        -9 => NS,
+        -6 => US,
+        -3 => MS,
+        0 => S,
+        e => {
+            return Err(WaveError::UnsupportedFeature(format!(
+                "FST timescale 1e{}s",
+                e
+            )));
+        }
+    };
+
+    // Timestamps are stored in fs
+    let time_factor = FS.divisor() / time_scale.divisor();
+
+    let text = |x: &str| Some(x.trim().to_string()).filter(|x| !x.is_empty());
+    let mut info = super::FileInfo {
+        date: text(&reader.get_header().date),
+        version: text(&reader.get_header().version),
+        comments: vec![],
+    };
+
+    // Signals are looked up by their handle when reading values. Aliases share one handle.
+    let mut handle_signals: HashMap<usize, Vec<Rc<RefCell<Signal>>>> = HashMap::new();
+    let mut scopes = vec![Rc::clone(&root)];
+
+    reader
+        .read_hierarchy(|entry| match entry {
+            FstHierarchyEntry::Scope { name, tpe, .. } => {
+                let parent = Rc::clone(scopes.last().unwrap());
+                // Other scope types, e.g. SystemVerilog ones, are shown as modules
+                let scope_type = match tpe {
+                    FstScopeType::Task => ScopeType::Task,
+                    FstScopeType::Function => ScopeType::Function,
+                    FstScopeType::Begin => ScopeType::Begin,
+                    FstScopeType::Fork => ScopeType::Fork,
+                    _ => ScopeType::Module,
+                };
+                let module = Rc::new(RefCell::new(Module {
+                    name,
+                    scope_type,
+                    depth: parent.borrow().depth + 1,
+                    signals: vec![],
+                    submodules: vec![],
+                    parent: Some(Rc::downgrade(&parent)),
+                }));
+                parent.borrow_mut().submodules.push(Rc::clone(&module));
+                scopes.push(module);
+            }
+            FstHierarchyEntry::UpScope if scopes.len() > 1 => {
+                scopes.pop();
+            }
+            FstHierarchyEntry::Var {
+                tpe,
+                name,
+                length,
+                handle,
+                ..
+            } => {
+                let parent = scopes.last().unwrap();
+                let code = IdCode::from(handle.get_index() as u64);
+                let var = Var::new(var_type_of(tpe), length, code, name, None);
+                let signal = Rc::new(RefCell::new(Signal::from_var(&var)));
+                signal.borrow_mut().parent_module = Some(Rc::downgrade(parent));
+                parent.borrow_mut().signals.push(Rc::clone(&signal));
+                handle_signals
+                    .entry(handle.get_index())
+                    .or_default()
+                    .push(signal);
+            }
+            FstHierarchyEntry::Comment { string } => info.comments.push(string.trim().to_string()),
+            _ => {}
+        })
+        .map_err(fst_error)?;
+
+    let mut warnings = vec![];
+    // Time in fs of the last event, and whether a later timestamp overflowed it
+    let (mut last_time, mut stopped) = (0, false);
+    reader
+        .read_signals(&FstFilter::all(), |t, handle, value| {
+            if stopped {
+                return;
+            }
+            let Some(time) = t.checked_mul(time_factor) else {
+                super::stop_reading(last_time, super::timestamp_overflow(t), &mut warnings);
+                stopped = true;
+                return;
+            };
+            last_time = time;
+            let value = match value {
+                FstSignalValue::String(bytes) => value_from_bytes(bytes),
+                // Real values are not supported yet
+                FstSignalValue::Real(_) => return,
+            };
+            if let Some(signals) = handle_signals.get(&handle.get_index()) {
+                signals
+                    .iter()
+                    .for_each(|x| x.borrow_mut().add_event(time, value.clone()));
+            }
+        })
+        .map_err(fst_error)?;
+
+    Ok((root, time_scale, warnings, info))
+}
@@ -1,7 +1,17 @@
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+#[cfg(feature = "fst")]
+pub mod fst;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod windowed;
+
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
+    collections::HashSet,
     fs::File,
-    io::{self, BufReader},
+    io::{self, BufRead, BufReader, Read},
+    iter::successors,
     rc::Rc,
 };
 
@@ -10,29 +20,381 @@ use ratatui::{
     style::Style,
     text::Span,
 };
-use vcd::{ScopeItem, TimescaleUnit, Value, Vector};
+use vcd::{
+    Command, Header, IdCode, ScopeItem, ScopeType, SimulationCommand, TimescaleUnit, Value, Vector,
+};
+
+use cli_log::warn;
 
 use crate::{
+    error::WaveError,
     module::Module,
     signal::{Signal, ValueType},
+    time::{Time, step_sequence},
+    ui::Glyphs,
 };
 
-pub fn parse_files(file_name: String) -> io::Result<(Rc<RefCell<Module>>, TimescaleUnit)> {
+/// Timescale used for VCD files without a `$timescale` header
+pub const DEFAULT_TIMESCALE: TimescaleUnit = TimescaleUnit::NS;
+
+/// The root module, the timescale unit, the warnings met while parsing and the metadata of a
+/// waveform file
+pub type ParsedFile = (Rc<RefCell<Module>>, TimescaleUnit, Vec<String>, FileInfo);
+
+/// Metadata of the header of a waveform file, e.g. the simulator which produced it
+/// - `date` - the text of `$date`
+/// - `version` - the text of `$version`, usually the simulator and its version
+/// - `comments` - the texts of the top-level `$comment` commands
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileInfo {
+    pub date: Option<String>,
+    pub version: Option<String>,
+    pub comments: Vec<String>,
+}
+
+impl FileInfo {
+    /// The metadata of a VCD header, with the surrounding whitespace of each text trimmed
+    ///
+    /// ```
+    /// use rata_wave::utils::parse_files;
+    ///
+    /// let (_, _, _, info) = parse_files("assets/verilog/test_1.vcd".to_string()).unwrap();
+    /// assert_eq!(info.date.as_deref(), Some("Fri Mar 21 21:58:27 2025"));
+    /// assert_eq!(info.version.as_deref(), Some("Icarus Verilog"));
+    /// assert!(info.comments.is_empty());
+    /// ```
+    pub fn from_header(header: &Header) -> Self {
+        let text = |x: &Option<String>| x.as_ref().map(|x| x.trim().to_string());
+        FileInfo {
+            date: text(&header.date),
+            version: text(&header.version),
+            comments: header
+                .items
+                .iter()
+                .filter_map(|x| match x {
+                    ScopeItem::Comment(comment) => Some(comment.trim().to_string()),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A reader counting the bytes consumed through it, i.e. the offset in the file
+pub(crate) struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R> CountingReader<R> {
+    pub(crate) fn new(inner: R, count: Rc<Cell<u64>>) -> Self {
+        CountingReader { inner, count }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.set(self.count.get() + n as u64);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.count.set(self.count.get() + amt as u64);
+    }
+}
+
+/// Path standing for the standard input, e.g. `vvp sim | rata_wave -`
+pub const STDIN_PATH: &str = "-";
+
+/// Parse a VCD file into a module tree, `STDIN_PATH` reads the file from the standard input
+/// Return the root module, the timescale unit of the file and the warnings met while parsing
+/// Signals without a value in a `$dumpoff` section are set to `x` at its time
+/// Timestamps going back in time are reported as a warning, and the events are sorted by time
+/// An error after the header, e.g. in a file cut short, is reported as a warning, keeping the
+/// events before it
+///
+/// ```
+/// use rata_wave::signal::ValueType;
+/// use rata_wave::utils::parse_files;
+/// use vcd::Value;
+///
+/// let (root, _, _, _) = parse_files("assets/verilog/dumpoff.vcd".to_string()).unwrap();
+/// let signals = root.borrow().get_signals();
+/// let value_at = |name: &str, time: u64| {
+///     let signal = signals.iter().find(|x| x.borrow().name == name).unwrap();
+///     signal.borrow().value_at(time).unwrap().to_string()
+/// };
+/// // Dumped off between 20ns and 30ns
+/// assert_eq!(value_at("clk", 15_000_000), "1");
+/// assert_eq!(value_at("clk", 25_000_000), "x");
+/// assert_eq!(value_at("bus", 25_000_000), "x");
+/// assert_eq!(value_at("clk", 35_000_000), "0");
+/// assert_eq!(value_at("bus", 35_000_000), "3");
+///
+/// let (root, _, warnings, _) = parse_files("assets/verilog/unordered.vcd".to_string()).unwrap();
+/// assert_eq!(warnings, vec!["Timestamp #10 comes after #20, the events were sorted by time"]);
+/// let clk = &root.borrow().get_signals()[0];
+/// let times: Vec<u64> = clk.borrow().events.iter().map(|(time, _)| *time).collect();
+/// assert_eq!(times, vec![0, 10_000_000, 20_000_000, 30_000_000]);
+///
+/// // Single-bit vectors of 1-bit vars are edges, scalars of wider vars are extended
+/// let (root, _, _, _) = parse_files("assets/verilog/mixed_width.vcd".to_string()).unwrap();
+/// let signals = root.borrow().get_signals();
+/// let en = signals.iter().find(|x| x.borrow().name == "en").unwrap();
+/// assert_eq!(en.borrow().value_at(10_000_000), Some(ValueType::Value(Value::V1)));
+/// let bus = signals.iter().find(|x| x.borrow().name == "bus").unwrap();
+/// assert!(matches!(bus.borrow().value_at(10_000_000), Some(ValueType::Vector(_))));
+///
+/// let (root, _, warnings, _) = parse_files("assets/verilog/truncated.vcd".to_string()).unwrap();
+/// assert_eq!(warnings.len(), 1);
+/// assert!(warnings[0].starts_with("Stopped reading at 20ns"));
+/// let clk = &root.borrow().get_signals()[0];
+/// assert_eq!(clk.borrow().value_at(20_000_000), Some(ValueType::Value(Value::V0)));
+///
+/// // Times in fs beyond 64 bits stop the reading too
+/// let (root, _, warnings, _) = parse_files("assets/verilog/overflow.vcd".to_string()).unwrap();
+/// assert!(warnings[0].ends_with("timestamp #100000 is too large for a time in fs, the later events are missing"));
+/// assert_eq!(root.borrow().get_signals()[0].borrow().events.len(), 2);
+/// ```
+pub fn parse_files(file_name: String) -> Result<ParsedFile, WaveError> {
+    parse_files_with_progress(file_name, &mut |_, _| {})
+}
+
+/// Same as `parse_files`, calling `progress` with the bytes read so far and the size of the
+/// file each time another percent of the file has been read
+/// The size of the standard input is unknown, so it is given as 0 and `progress` is called
+/// after each MiB read
+pub fn parse_files_with_progress(
+    file_name: String,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<ParsedFile, WaveError> {
+    parse_files_in_scope(file_name, None, progress)
+}
+
+/// Same as `parse_files_with_progress`, only keeping the module given by its path (e.g.
+/// `top.cpu.core0`, see `Module::find_module`) and its submodules when `scope` is given
+/// The modules above the scope are kept without their other submodules and signals, and only
+/// the events of the signals in the scope are recorded
+///
+/// ```
+/// use rata_wave::utils::parse_files_in_scope;
+///
+/// let file_name = "assets/verilog/test_1.vcd".to_string();
+/// let (root, _, _, _) = parse_files_in_scope(file_name.clone(), Some("test_tb.i1"), &mut |_, _| {})
+///     .unwrap();
+/// let signals = root.borrow().get_signals();
+/// assert!(!signals.is_empty());
+/// assert!(signals.iter().all(|x| x.borrow().output_path().starts_with("test_tb->i1:")));
+/// assert!(signals.iter().all(|x| !x.borrow().events.is_empty()));
+///
+/// assert!(parse_files_in_scope(file_name, Some("test_tb.i2"), &mut |_, _| {}).is_err());
+/// ```
+pub fn parse_files_in_scope(
+    file_name: String,
+    scope: Option<&str>,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<ParsedFile, WaveError> {
+    let (reader, total): (Box<dyn BufRead>, u64) = match file_name.as_str() {
+        STDIN_PATH => (Box::new(io::stdin().lock()), 0),
+        _ => {
+            let file = File::open(file_name)?;
+            let total = file.metadata()?.len();
+            (Box::new(BufReader::new(file)), total)
+        }
+    };
+    let count = Rc::new(Cell::new(0));
+    let mut parser = vcd::Parser::new(CountingReader::new(reader, Rc::clone(&count)));
+
+    // Parse the header and find the wires
+    let header = parser.parse_header()?;
+
+    let mut warnings = vec![];
+    let (time_factor, time_scale) = time_factor_of(&header, &mut warnings);
+    let root = build_root(&header);
+    let codes = scope.map(|x| restrict_to_scope(&root, x)).transpose()?;
+    let in_scope = |id: &IdCode| codes.as_ref().is_none_or(|x| x.contains(id));
+    let mut dump_off = DumpOff::new(&header);
+
+    let mut cur_time_stamp = 0;
+    let mut unordered = false;
+    let mut percent = None;
+    for command_result in parser {
+        // Keep the events before an error, e.g. in the dump of a crashed simulation
+        let command = match command_result {
+            Ok(command) => command,
+            Err(e) => {
+                stop_reading(cur_time_stamp, e.into(), &mut warnings);
+                break;
+            }
+        };
+
+        let new_percent = match total {
+            0 => count.get() >> 20,
+            _ => count.get() * 100 / total,
+        };
+        if percent != Some(new_percent) {
+            percent = Some(new_percent);
+            progress(count.get(), total);
+        }
+
+        for (id, value) in dump_off.handle(&command) {
+            if in_scope(&id) {
+                root.borrow_mut().add_event(id, cur_time_stamp, value);
+            }
+        }
+
+        use vcd::Command::*;
+        match command {
+            Timestamp(t) => {
+                let Some(time) = t.checked_mul(time_factor) else {
+                    stop_reading(cur_time_stamp, timestamp_overflow(t), &mut warnings);
+                    break;
+                };
+                if time < cur_time_stamp && !unordered {
+                    unordered = true;
+                    warnings.push(format!(
+                        "Timestamp #{} comes after #{}, the events were sorted by time",
+                        t,
+                        cur_time_stamp / time_factor
+                    ));
+                }
+                cur_time_stamp = time;
+            }
+            ChangeScalar(id, value) if in_scope(&id) => {
+                root.borrow_mut()
+                    .add_event(id, cur_time_stamp, ValueType::Value(value));
+            }
+            ChangeVector(id, vector) if in_scope(&id) => {
+                root.borrow_mut()
+                    .add_event(id, cur_time_stamp, ValueType::Vector(vector));
+            }
+            _ => (),
+        }
+    }
+
+    // Events are expected in time order, see `Signal::events`
+    if unordered {
+        root.borrow()
+            .get_signals()
+            .iter()
+            .for_each(|x| x.borrow_mut().events.sort_by_key(|(time, _)| *time));
+    }
+
+    Ok((root, time_scale, warnings, FileInfo::from_header(&header)))
+}
+
+/// Tracker of the `$dumpoff` sections of a VCD file
+/// The values of a `$dumpoff` section are usually all `x`, but writers may leave them out, so the
+/// signals without a value in the section are set to `x` at its end
+#[derive(Clone)]
+pub(crate) struct DumpOff {
+    // `x` value of each code, sized after its variable
+    x_values: Vec<(IdCode, ValueType)>,
+    // Codes given a value in the current `$dumpoff` section, if any
+    listed: Option<HashSet<IdCode>>,
+}
+
+impl DumpOff {
+    pub(crate) fn new(header: &Header) -> Self {
+        fn collect(
+            items: &[ScopeItem],
+            codes: &mut HashSet<IdCode>,
+            x_values: &mut Vec<(IdCode, ValueType)>,
+        ) {
+            items.iter().for_each(|x| match x {
+                ScopeItem::Scope(scope) => collect(&scope.items, codes, x_values),
+                ScopeItem::Var(var) if codes.insert(var.code) => {
+                    let value = match var.size {
+                        1 => ValueType::Value(Value::X),
+                        size => ValueType::Vector(Vector::filled(Value::X, size as usize)),
+                    };
+                    x_values.push((var.code, value));
+                }
+                _ => {}
+            })
+        }
+        let mut x_values = vec![];
+        collect(&header.items, &mut HashSet::new(), &mut x_values);
+        Self {
+            x_values,
+            listed: None,
+        }
+    }
+
+    /// Follow the given command, return the `x` values to add at the end of a `$dumpoff` section
+    pub(crate) fn handle(&mut self, command: &Command) -> Vec<(IdCode, ValueType)> {
+        use Command::*;
+        match (command, &mut self.listed) {
+            (Begin(SimulationCommand::Dumpoff), _) => {
+                self.listed = Some(HashSet::new());
+            }
+            (ChangeScalar(id, _) | ChangeVector(id, _), Some(listed)) => {
+                listed.insert(*id);
+            }
+            (End(SimulationCommand::Dumpoff), Some(listed)) => {
+                let values = self
+                    .x_values
+                    .iter()
+                    .filter(|(id, _)| !listed.contains(id))
+                    .cloned()
+                    .collect();
+                self.listed = None;
+                return values;
+            }
+            _ => {}
+        }
+        vec![]
+    }
+}
+
+/// Warn that the file is only read up to `time` (in fs) because of `error`, keeping the events
+/// before it, e.g. in the dump of a crashed simulation
+pub(crate) fn stop_reading(time: u64, error: WaveError, warnings: &mut Vec<String>) {
+    let message = format!(
+        "Stopped reading at {}: {}, the later events are missing",
+        Time::new(time, TimescaleUnit::FS),
+        error
+    );
+    warn!("{}", message);
+    warnings.push(message);
+}
+
+/// Error of a timestamp whose time in fs does not fit in 64 bits
+pub(crate) fn timestamp_overflow(t: u64) -> WaveError {
+    WaveError::UnsupportedFeature(format!("timestamp #{} is too large for a time in fs", t))
+}
+
+/// Time factor converting the timestamps of the file to fs, and the timescale unit of the file
+/// A missing timescale falls back to `DEFAULT_TIMESCALE` with the warning of
+/// `WaveError::NoTimescale`
+pub(crate) fn time_factor_of(header: &Header, warnings: &mut Vec<String>) -> (u64, TimescaleUnit) {
+    let (time_scale_num, time_scale) = header.timescale.unwrap_or_else(|| {
+        warnings.push(WaveError::NoTimescale.to_string());
+        (1, DEFAULT_TIMESCALE)
+    });
+    let time_factor = time_scale_num as u64 * (TimescaleUnit::FS.divisor() / time_scale.divisor());
+    (time_factor, time_scale)
+}
+
+/// Build the module tree declared in the header, without any event
+pub(crate) fn build_root(header: &Header) -> Rc<RefCell<Module>> {
     let root = Rc::new(RefCell::new(Module {
         name: String::from("Root"),
+        scope_type: ScopeType::Module,
         depth: 1,
         signals: vec![],
         submodules: vec![],
         parent: None,
     }));
 
-    let mut parser = vcd::Parser::new(BufReader::new(File::open(file_name)?));
-
-    // Parse the header and find the wires
-    let header = parser.parse_header()?;
-
-    assert!(header.timescale.unwrap().0 == 1);
-
     header.items.iter().for_each(|x| {
         use ScopeItem::*;
         match x {
@@ -61,39 +423,68 @@ pub fn parse_files(file_name: String) -> io::Result<(Rc<RefCell<Module>>, Timesc
         .iter()
         .for_each(|x| x.borrow_mut().parent_module = Some(Rc::downgrade(&root)));
 
-    let mut cur_time_stamp = 0;
-    for command_result in parser {
-        let command = command_result?;
-        use vcd::Command::*;
-        match command {
-            Timestamp(t) => {
-                cur_time_stamp = t;
-            }
-            ChangeScalar(id, value) => {
-                root.borrow_mut()
-                    .add_event(id, cur_time_stamp, ValueType::Value(value));
-            }
-            ChangeVector(id, vector) => {
-                root.borrow_mut()
-                    .add_event(id, cur_time_stamp, ValueType::Vector(vector));
-            }
-            _ => (),
-        }
+    root
+}
+
+/// Drop the modules and signals outside the module given by its path (see `Module::find_module`)
+/// The modules above the scope only keep the submodule leading to it, so paths are unchanged
+/// Return the codes of the signals in the scope
+pub(crate) fn restrict_to_scope(
+    root: &Rc<RefCell<Module>>,
+    scope: &str,
+) -> Result<HashSet<IdCode>, WaveError> {
+    let module = root
+        .borrow()
+        .find_module(scope)
+        .ok_or_else(|| WaveError::UnknownScope(scope.to_string()))?;
+
+    let mut node = Rc::clone(&module);
+    loop {
+        let parent = node.borrow().parent.as_ref().and_then(|x| x.upgrade());
+        let Some(parent) = parent else {
+            break;
+        };
+        parent.borrow_mut().signals.clear();
+        parent
+            .borrow_mut()
+            .submodules
+            .retain(|x| Rc::ptr_eq(x, &node));
+        node = parent;
     }
 
-    Ok((root, header.timescale.unwrap().1))
+    let codes = module
+        .borrow()
+        .get_signals()
+        .iter()
+        .map(|x| x.borrow().code)
+        .collect();
+    Ok(codes)
 }
 
-pub fn middle_str<'a>(length: usize, mid_str: String) -> Vec<Span<'a>> {
-    let len = mid_str.len();
+/// Center `mid_str` in `length` cells, one span per cell
+/// If `mid_str` does not fit, every cell is filled with the overflow glyph, e.g. `␩`
+///
+/// ```
+/// use rata_wave::ui::{ASCII_GLYPHS, UNICODE_GLYPHS};
+/// use rata_wave::utils::middle_str;
+///
+/// let arr = middle_str(5, "µs".to_string(), &UNICODE_GLYPHS);
+/// assert_eq!(arr.len(), 5);
+/// assert_eq!(arr[1].content, "µ");
+/// assert_eq!(arr[2].content, "s");
+///
+/// assert_eq!(middle_str(2, "abc".to_string(), &UNICODE_GLYPHS)[0].content, "␩");
+/// assert_eq!(middle_str(2, "abc".to_string(), &ASCII_GLYPHS)[0].content, "~");
+/// ```
+pub fn middle_str<'a>(length: usize, mid_str: String, glyphs: &Glyphs) -> Vec<Span<'a>> {
+    let len = mid_str.chars().count();
     if len > length {
-        return vec![Span::styled("␩", Style::default()); length];
+        return vec![Span::styled(glyphs.overflow, Style::default()); length];
     }
     let mut arr = vec![];
     for _ in 0..length {
         arr.push(Span::styled(" ", Style::default()));
     }
-    // ␩
     arr.splice(
         length / 2 - len / 2..length / 2 - len / 2 + len,
         mid_str
@@ -106,6 +497,202 @@ pub fn middle_str<'a>(length: usize, mid_str: String) -> Vec<Span<'a>> {
     arr
 }
 
+/// Default minimum number of columns between two ticks of the time ruler
+pub const RULER_TICK_SPACING: u64 = 10;
+
+/// Build the two lines of the time ruler of `width` columns, the tick labels and the `|` ticks
+/// Ticks are put at round times, i.e. multiples of a value of the 1-2-5 sequence, at least
+/// `RULER_TICK_SPACING` columns apart
+/// Each label ends right above its tick, and labels overlapping the previous one are left out
+/// Labels have just enough digits to tell two ticks apart, see `Time::format`
+/// - `time_start` - the time of the first column in fs
+/// - `time_step` - the time of a column in fs
+///
+/// ```
+/// use rata_wave::utils::ruler;
+///
+/// let (labels, ticks) = ruler(0, 1000, 25);
+/// assert_eq!(labels, "0fs    10ps      20ps    ");
+/// assert_eq!(ticks, "|         |         |    ");
+///
+/// // Labels wider than the tick spacing are thinned out
+/// let (labels, ticks) = ruler(1_234_567_893, 1, 30);
+/// assert_eq!(ticks, "       |         |         |  ");
+/// assert_eq!(labels, "1.2345679us     1.23456792us  ");
+///
+/// // Each label is in the natural unit of its tick
+/// let (labels, ticks) = ruler(999_980_000_000, 1_000_000, 40);
+/// assert_eq!(ticks, "|         |         |         |         ");
+/// assert_eq!(labels, "999.98us          1ms 1.00001ms         ");
+/// ```
+pub fn ruler(time_start: u64, time_step: u64, width: usize) -> (String, String) {
+    ruler_with_spacing(time_start, time_step, width, RULER_TICK_SPACING, 0)
+}
+
+/// Same as `ruler`, with ticks at least `tick_spacing` columns apart, and labels giving the
+/// time relative to `origin` (in fs), negative before it
+///
+/// ```
+/// use rata_wave::utils::ruler_with_spacing;
+///
+/// let (labels, ticks) = ruler_with_spacing(0, 1000, 25, 5, 0);
+/// assert_eq!(ticks, "|    |    |    |    |    ");
+/// assert_eq!(labels, "0fs    10ps 15ps 20ps    ");
+///
+/// // Ticks are at round times from the origin
+/// let (labels, ticks) = ruler_with_spacing(0, 1000, 30, 10, 13_000);
+/// assert_eq!(ticks, "   |         |         |      ");
+/// assert_eq!(labels, "-10ps      0fs      10ps      ");
+/// ```
+pub fn ruler_with_spacing(
+    time_start: u64,
+    time_step: u64,
+    width: usize,
+    tick_spacing: u64,
+    origin: u64,
+) -> (String, String) {
+    let time_step = time_step.max(1);
+    let interval = step_sequence()
+        .find(|x| *x >= time_step.saturating_mul(tick_spacing.max(1)))
+        .unwrap_or(u64::MAX);
+
+    let mut labels = vec![' '; width];
+    let mut ticks = vec![' '; width];
+    let mut last_label_end = None;
+    // Times relative to the origin, which do not fit in u64 before it
+    let (interval, origin) = (interval as i128, origin as i128);
+    let mut relative = (time_start as i128 - origin).div_euclid(interval) * interval;
+    if relative + origin < time_start as i128 {
+        relative += interval;
+    }
+    loop {
+        let time = relative + origin;
+        let column = (time - time_start as i128) / time_step as i128;
+        if column >= width as i128 || time > u64::MAX as i128 {
+            break;
+        }
+        let column = column as usize;
+        ticks[column] = '|';
+
+        // Enough digits to tell two ticks apart
+        let tick = Time::new(relative.unsigned_abs() as u64, TimescaleUnit::FS);
+        let unit_fs = (TimescaleUnit::FS.divisor() / tick.unit().divisor()) as i128;
+        let precision = successors(Some(interval), |x| x.checked_mul(10))
+            .take_while(|x| *x < unit_fs)
+            .count();
+        let sign = if relative < 0 { "-" } else { "" };
+        let label: Vec<char> = format!("{}{}", sign, tick.format(None, precision))
+            .chars()
+            .collect();
+        let start = (column + 1).saturating_sub(label.len());
+        let end = start + label.len();
+        if end <= width && last_label_end.is_none_or(|last_end| start > last_end) {
+            labels[start..end].copy_from_slice(&label);
+            last_label_end = Some(end);
+        }
+
+        relative += interval;
+    }
+    (labels.into_iter().collect(), ticks.into_iter().collect())
+}
+
+/// Build the line of `width` columns above the time ruler marking the bookmarks in view
+/// Each bookmark is drawn as the bookmark glyph (e.g. `▼`) at its column followed by its label,
+/// cut before the next one
+/// - `bookmarks` - the times in fs and the labels of the bookmarks, sorted by time
+/// - `time_start` - the time of the first column in fs
+/// - `time_step` - the time of a column in fs
+///
+/// ```
+/// use rata_wave::ui::{ASCII_GLYPHS, UNICODE_GLYPHS};
+/// use rata_wave::utils::bookmark_line;
+///
+/// let bookmarks = vec![
+///     (0, "reset".to_string()),
+///     (60, "bug".to_string()),
+///     (100, "done".to_string()),
+///     (500, "later".to_string()),
+/// ];
+/// let line = |start, width| bookmark_line(&bookmarks, start, 10, width, &UNICODE_GLYPHS);
+/// assert_eq!(line(0, 14), "▼reset▼bug▼don");
+/// assert_eq!(line(30, 6), "   ▼bu");
+/// assert_eq!(bookmark_line(&bookmarks, 30, 10, 6, &ASCII_GLYPHS), "   vbu");
+/// ```
+pub fn bookmark_line(
+    bookmarks: &[(u64, String)],
+    time_start: u64,
+    time_step: u64,
+    width: usize,
+    glyphs: &Glyphs,
+) -> String {
+    let time_step = time_step.max(1);
+    let mut line = vec![' '; width];
+    let columns: Vec<(usize, &String)> = bookmarks
+        .iter()
+        .filter(|(time, _)| *time >= time_start)
+        .map(|(time, label)| (((time - time_start) / time_step) as usize, label))
+        .take_while(|(column, _)| *column < width)
+        .collect();
+    for (i, (column, label)) in columns.iter().enumerate() {
+        let end = columns.get(i + 1).map_or(width, |(next, _)| *next);
+        line[*column] = glyphs.bookmark;
+        line.iter_mut()
+            .take(end)
+            .skip(column + 1)
+            .zip(label.chars())
+            .for_each(|(cell, c)| *cell = c);
+    }
+    line.into_iter().collect()
+}
+
+/// Index in `ui::D_MULTIPLE` of a column with `count` transitions
+/// 2 transitions give 0, up to 4 give 1, up to 8 give 2 and more give 3
+///
+/// ```
+/// use rata_wave::utils::density_index;
+///
+/// let indexes: Vec<usize> = [2, 3, 4, 5, 8, 9, 1000].into_iter().map(density_index).collect();
+/// assert_eq!(indexes, vec![0, 1, 1, 2, 2, 3, 3]);
+/// ```
+pub fn density_index(count: usize) -> usize {
+    let bits = (usize::BITS - count.saturating_sub(1).leading_zeros()) as usize;
+    bits.saturating_sub(1).min(3)
+}
+
+/// Shorten `s` to at most `width` characters by replacing its middle with the ellipsis glyph,
+/// e.g. `…`
+/// Too narrow for the ellipsis, `s` is only cut
+///
+/// ```
+/// use rata_wave::ui::{ASCII_GLYPHS, UNICODE_GLYPHS};
+/// use rata_wave::utils::elide_middle;
+///
+/// let elide = |width| elide_middle("test_tb:i1:clk", width, &UNICODE_GLYPHS);
+/// assert_eq!(elide(20), "test_tb:i1:clk");
+/// assert_eq!(elide(9), "test…:clk");
+/// assert_eq!(elide(0), "");
+/// assert_eq!(elide_middle("test_tb:i1:clk", 9, &ASCII_GLYPHS), "tes...clk");
+/// assert_eq!(elide_middle("test_tb:i1:clk", 2, &ASCII_GLYPHS), "te");
+/// ```
+pub fn elide_middle(s: &str, width: usize, glyphs: &Glyphs) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= width {
+        return s.to_string();
+    }
+    let ellipsis = glyphs.ellipsis.chars().count();
+    if width < ellipsis {
+        return chars[..width].iter().collect();
+    }
+    let tail = (width - ellipsis) / 2;
+    let head = width - ellipsis - tail;
+    chars[..head]
+        .iter()
+        .copied()
+        .chain(glyphs.ellipsis.chars())
+        .chain(chars[chars.len() - tail..].iter().copied())
+        .collect()
+}
+
 pub fn vector_contain_x_or_z(vector: &Vector) -> bool {
     vector
         .iter()
@@ -0,0 +1,119 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, channel},
+    time::{Duration, Instant},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::STDIN_PATH;
+
+/// Time the files must stay unchanged before a change is reported, so that a simulator
+/// flushing the file in several writes only triggers one reload
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Time between two checks of the events of the watcher, when no key is pressed
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Watch waveform files for changes with the notifications of the file system
+/// The directories of the files are watched, so that a file replaced by a rename is still seen
+/// The standard input is not watched
+pub struct FileWatcher {
+    // Stops watching when dropped
+    _watcher: Option<RecommendedWatcher>,
+    events: Receiver<notify::Result<Event>>,
+    // Absolute paths of the watched files
+    files: Vec<PathBuf>,
+    // When the last change was seen, until it is reported
+    changed_at: Option<Instant>,
+}
+
+impl FileWatcher {
+    /// Watch the files, or nothing if the file system cannot notify the changes
+    pub fn new(file_paths: &[String]) -> Self {
+        let files: Vec<PathBuf> = file_paths
+            .iter()
+            .filter(|x| *x != STDIN_PATH)
+            .filter_map(|x| fs::canonicalize(x).ok())
+            .collect();
+        let (sender, events) = channel();
+        let watcher = notify::recommended_watcher(sender).ok().map(|mut watcher| {
+            let mut directories: Vec<&Path> = files.iter().filter_map(|x| x.parent()).collect();
+            directories.sort();
+            directories.dedup();
+            for directory in directories {
+                let _ = watcher.watch(directory, RecursiveMode::NonRecursive);
+            }
+            watcher
+        });
+        FileWatcher {
+            _watcher: watcher,
+            events,
+            files,
+            changed_at: None,
+        }
+    }
+
+    /// Whether a change was seen and is not reported yet
+    pub fn pending(&self) -> bool {
+        self.changed_at.is_some()
+    }
+
+    /// Check the events, return true once the files changed and then stayed unchanged for
+    /// `DEBOUNCE`
+    pub fn poll(&mut self) -> bool {
+        self.poll_at(Instant::now())
+    }
+
+    /// `FileWatcher::poll` at the time `now`
+    ///
+    /// ```
+    /// use rata_wave::utils::watch::{DEBOUNCE, FileWatcher};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let path = std::env::temp_dir().join("ratawave_watch_doctest.vcd");
+    /// std::fs::write(&path, "#0").unwrap();
+    /// let mut watcher = FileWatcher::new(&[path.display().to_string()]);
+    /// let now = Instant::now();
+    /// assert!(!watcher.poll_at(now));
+    ///
+    /// std::fs::write(&path, "#0\n#10").unwrap();
+    /// // The notification arrives from another thread
+    /// let deadline = now + Duration::from_secs(10);
+    /// while !watcher.pending() && Instant::now() < deadline {
+    ///     assert!(!watcher.poll_at(now));
+    ///     std::thread::sleep(Duration::from_millis(10));
+    /// }
+    /// // The change is seen, with the later notifications of the same write
+    /// assert!(watcher.pending());
+    /// std::thread::sleep(Duration::from_millis(200));
+    /// assert!(!watcher.poll_at(now));
+    /// // The file may still be written
+    /// assert!(!watcher.poll_at(now + DEBOUNCE / 2));
+    /// assert!(watcher.poll_at(now + DEBOUNCE));
+    /// assert!(!watcher.poll_at(now + DEBOUNCE * 2));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn poll_at(&mut self, now: Instant) -> bool {
+        // All the pending events are taken, a write usually sends several
+        let changes = self
+            .events
+            .try_iter()
+            .filter_map(Result::ok)
+            .filter(|event| !matches!(event.kind, EventKind::Access(_)))
+            .filter(|event| event.paths.iter().any(|x| self.files.contains(x)))
+            .count();
+        if changes > 0 {
+            self.changed_at = Some(now);
+            return false;
+        }
+        match self.changed_at {
+            Some(time) if now.saturating_duration_since(time) >= DEBOUNCE => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Seek, SeekFrom},
+    rc::Rc,
+};
+
+use vcd::IdCode;
+
+use super::{
+    CountingReader, DumpOff, FileInfo, ParsedFile, build_root, restrict_to_scope, stop_reading,
+    time_factor_of, timestamp_overflow,
+};
+use crate::{
+    error::WaveError,
+    signal::{Signal, ValueType},
+};
+
+/// Bytes of the file between two checkpoints, by default
+pub const CHECKPOINT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// A point of the file where parsing can restart
+struct Checkpoint {
+    // Time of the checkpoint in fs
+    time: u64,
+    // Offset in the file of the command following the checkpoint
+    offset: u64,
+    // Values of all signals at the checkpoint
+    values: HashMap<IdCode, ValueType>,
+}
+
+/// Index of a VCD file built by a first pass over it, used to load only the events of a time
+/// window into the signals, re-reading the file when the window moves
+pub struct WindowedStore {
+    file_name: String,
+    time_factor: u64,
+    dump_off: DumpOff,
+    checkpoints: Vec<Checkpoint>,
+    // Signals of each code, aliases share a code
+    signals: HashMap<IdCode, Vec<Rc<RefCell<Signal>>>>,
+    // Time of the last timestamp in fs
+    max_time: u64,
+    // Whether a timestamp goes back in time
+    unordered: bool,
+    // Time range of the loaded events in fs
+    loaded: Option<(u64, u64)>,
+}
+
+/// Index a VCD file for windowed loading
+/// Return the parsed file, whose signals have no event yet, and the index
+/// Only the signals in `scope` are kept when it is given, see `parse_files_in_scope`
+/// - `checkpoint_bytes` - bytes of the file between two checkpoints, `CHECKPOINT_BYTES` unless
+///   testing
+///
+/// Loading from the checkpoints in the middle of the file gives the values of a full load:
+///
+/// ```
+/// use rata_wave::utils::windowed::index_file;
+///
+/// let path = "assets/verilog/test_1.vcd";
+/// let full = rata_wave::load(path).unwrap();
+/// let full = full.root().borrow().all_signals_with_paths();
+/// // A checkpoint at almost every timestamp
+/// let ((root, ..), mut store) = index_file(path.to_string(), None, 64, &mut |_, _| {}).unwrap();
+/// let windowed = root.borrow().all_signals_with_paths();
+///
+/// let ns = 1_000_000;
+/// for (start, end) in [(0, 30), (95, 125), (400, 410), (333, 777), (990, 1100)] {
+///     store.load_window(start * ns, end * ns).unwrap();
+///     for ((path, signal), (_, expected)) in windowed.iter().zip(&full) {
+///         for time in (start * ns..=end * ns).step_by(ns as usize / 2) {
+///             let value = signal.borrow().value_at(time);
+///             assert_eq!(value, expected.borrow().value_at(time), "{} at {}", path, time);
+///         }
+///     }
+/// }
+/// ```
+pub fn index_file(
+    file_name: String,
+    scope: Option<&str>,
+    checkpoint_bytes: u64,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<(ParsedFile, WindowedStore), WaveError> {
+    let file = File::open(&file_name)?;
+    let total = file.metadata()?.len();
+    let count = Rc::new(Cell::new(0));
+    let mut parser = vcd::Parser::new(CountingReader::new(BufReader::new(file), Rc::clone(&count)));
+
+    let header = parser.parse_header()?;
+    let mut warnings = vec![];
+    let (time_factor, time_scale) = time_factor_of(&header, &mut warnings);
+    let root = build_root(&header);
+    if let Some(scope) = scope {
+        restrict_to_scope(&root, scope)?;
+    }
+    let mut dump_off = DumpOff::new(&header);
+
+    let mut signals: HashMap<IdCode, Vec<Rc<RefCell<Signal>>>> = HashMap::new();
+    root.borrow().get_signals().into_iter().for_each(|x| {
+        signals
+            .entry(x.borrow().code)
+            .or_default()
+            .push(Rc::clone(&x))
+    });
+
+    // Changes before the first timestamp are at time 0
+    let mut checkpoints = vec![Checkpoint {
+        time: 0,
+        offset: count.get(),
+        values: HashMap::new(),
+    }];
+    let mut values = HashMap::new();
+    let mut max_time = 0;
+    let mut unordered = false;
+    let mut percent = None;
+    loop {
+        let offset = count.get();
+        let Some(command) = parser.next() else {
+            break;
+        };
+
+        let new_percent = count.get() * 100 / total.max(1);
+        if percent != Some(new_percent) {
+            percent = Some(new_percent);
+            progress(count.get(), total);
+        }
+
+        // Keep the events before an error, as when the file is fully loaded
+        let command = match command {
+            Ok(command) => command,
+            Err(e) => {
+                stop_reading(max_time, e.into(), &mut warnings);
+                break;
+            }
+        };
+        values.extend(
+            dump_off
+                .handle(&command)
+                .into_iter()
+                .filter(|(id, _)| signals.contains_key(id)),
+        );
+
+        use vcd::Command::*;
+        match command {
+            Timestamp(t) => {
+                let Some(time) = t.checked_mul(time_factor) else {
+                    stop_reading(max_time, timestamp_overflow(t), &mut warnings);
+                    break;
+                };
+                if time < max_time && !unordered {
+                    unordered = true;
+                    warnings.push(format!(
+                        "Timestamp #{} comes after #{}, events may be missing in windowed mode",
+                        t,
+                        max_time / time_factor
+                    ));
+                }
+                max_time = max_time.max(time);
+                if offset - checkpoints[checkpoints.len() - 1].offset >= checkpoint_bytes {
+                    checkpoints.push(Checkpoint {
+                        time,
+                        offset,
+                        values: values.clone(),
+                    });
+                }
+            }
+            ChangeScalar(id, value) if signals.contains_key(&id) => {
+                values.insert(id, ValueType::Value(value));
+            }
+            ChangeVector(id, vector) if signals.contains_key(&id) => {
+                values.insert(id, ValueType::Vector(vector));
+            }
+            _ => (),
+        }
+    }
+
+    let store = WindowedStore {
+        file_name,
+        time_factor,
+        dump_off,
+        checkpoints,
+        signals,
+        max_time,
+        unordered,
+        loaded: None,
+    };
+    Ok((
+        (root, time_scale, warnings, FileInfo::from_header(&header)),
+        store,
+    ))
+}
+
+impl WindowedStore {
+    /// Time of the last timestamp in the file in fs
+    pub fn max_time(&self) -> u64 {
+        self.max_time
+    }
+
+    /// Make sure the events between `time_start` and `time_end` (in fs) are loaded into the
+    /// signals, replacing the previously loaded events
+    /// One more window is loaded on each side, so that scrolling does not always re-read the file
+    /// The value of each signal at the start of the loaded range is added as an event at the
+    /// checkpoint the loading starts from
+    pub fn load_window(&mut self, time_start: u64, time_end: u64) -> Result<(), WaveError> {
+        if let Some((start, end)) = self.loaded
+            && start <= time_start
+            && time_end <= end
+        {
+            return Ok(());
+        }
+        let span = time_end.saturating_sub(time_start);
+        let (start, end) = (
+            time_start.saturating_sub(span),
+            time_end.saturating_add(span),
+        );
+
+        let index = self.checkpoints.partition_point(|x| x.time <= start);
+        let checkpoint = &self.checkpoints[index.saturating_sub(1)];
+
+        self.signals
+            .values()
+            .flatten()
+            .for_each(|x| x.borrow_mut().events.clear());
+        let add_event = |id: IdCode, time: u64, value: ValueType| {
+            if let Some(signals) = self.signals.get(&id) {
+                signals
+                    .iter()
+                    .for_each(|x| x.borrow_mut().add_event(time, value.clone()));
+            }
+        };
+        checkpoint
+            .values
+            .iter()
+            .for_each(|(id, value)| add_event(*id, checkpoint.time, value.clone()));
+
+        let mut file = File::open(&self.file_name)?;
+        file.seek(SeekFrom::Start(checkpoint.offset))?;
+        let parser = vcd::Parser::new(BufReader::new(file));
+
+        // Checkpoints are at timestamps, so never inside a `$dumpoff` section
+        let mut dump_off = self.dump_off.clone();
+        let mut cur_time_stamp = checkpoint.time;
+        for command_result in parser {
+            // The error was reported when the file was indexed
+            let Ok(command) = command_result else {
+                break;
+            };
+            for (id, value) in dump_off.handle(&command) {
+                add_event(id, cur_time_stamp, value);
+            }
+
+            use vcd::Command::*;
+            match command {
+                Timestamp(t) => {
+                    // Indexing stopped at a timestamp that overflows
+                    let Some(time) = t.checked_mul(self.time_factor) else {
+                        break;
+                    };
+                    cur_time_stamp = time;
+                    if cur_time_stamp > end {
+                        break;
+                    }
+                }
+                ChangeScalar(id, value) => add_event(id, cur_time_stamp, ValueType::Value(value)),
+                ChangeVector(id, vector) => {
+                    add_event(id, cur_time_stamp, ValueType::Vector(vector))
+                }
+                _ => (),
+            }
+        }
+
+        if self.unordered {
+            self.signals
+                .values()
+                .flatten()
+                .for_each(|x| x.borrow_mut().events.sort_by_key(|(time, _)| *time));
+        }
+
+        self.loaded = Some((start, end));
+        Ok(())
+    }
+}